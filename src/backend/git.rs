@@ -0,0 +1,364 @@
+use std::{
+    fs::{read, remove_file, write},
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::{
+        OnceLock,
+        mpsc::{Sender, channel},
+    },
+    thread,
+};
+
+use anyhow::{Context, Result, ensure};
+
+use super::{FILE_EXTENSION, InternalNote};
+
+static TEXT_EXTENSION: &str = "txt";
+static TIME_FORMAT: &str = "format:%Y-%m-%d %H:%M";
+static DEFAULT_NAME: &str = "todotui";
+static DEFAULT_EMAIL: &str = "todotui@localhost";
+
+/// Commit of the data directory changing a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub id: String,
+    pub time: String,
+    pub summary: String,
+}
+
+fn run(folder: &Path, args: &[&str]) -> Result<Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(folder)
+        .args(args)
+        .output()
+        .context("Cannot run git")
+}
+
+fn git(folder: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = run(folder, args)?;
+    ensure!(
+        output.status.success(),
+        "git {} failed: {}",
+        args[0],
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(output.stdout)
+}
+
+/// Store `content` in the repository, its hash is returned
+fn hash_object(folder: &Path, content: &[u8]) -> Result<String> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(folder)
+        .args(["hash-object", "-w", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Cannot run git")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content)?;
+    }
+    let output = child.wait_with_output()?;
+    ensure!(
+        output.status.success(),
+        "git hash-object failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Work of the thread running git, so that a slow repository doesn't block the interface
+enum Job {
+    /// Files named relative to `folder` with their content when queued, `None` once removed
+    Commit {
+        folder: PathBuf,
+        files: Vec<(String, Option<Vec<u8>>)>,
+        message: String,
+    },
+    /// Answered once the jobs sent before are done
+    Flush(Sender<()>),
+}
+
+static WORKER: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Jobs run one at a time in the order they are sent
+fn worker() -> &'static Sender<Job> {
+    WORKER.get_or_init(|| {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            for job in receiver {
+                match job {
+                    // The commits are best effort, the notes themselves are saved
+                    Job::Commit {
+                        folder,
+                        files,
+                        message,
+                    } => {
+                        let _ = commit_files(&folder, &files, &message);
+                    }
+                    Job::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        sender
+    })
+}
+
+/// The files are read right away, a later save can't slip into this commit
+fn queue(folder: &Path, paths: Vec<PathBuf>, message: &str) {
+    let files = paths
+        .iter()
+        .map(|path| (name(path), read(path).ok()))
+        .collect();
+    let _ = worker().send(Job::Commit {
+        folder: folder.to_path_buf(),
+        files,
+        message: message.to_string(),
+    });
+}
+
+/// Wait for the commits sent so far
+pub(super) fn flush() {
+    if let Some(worker) = WORKER.get() {
+        let (done, wait) = channel();
+        if worker.send(Job::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+}
+
+fn folder(note_path: &Path) -> &Path {
+    note_path.parent().unwrap_or(Path::new("."))
+}
+
+fn name(path: &Path) -> String {
+    path.file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().to_string())
+}
+
+/// Name of the file relative to the data directory, as git expects it
+fn relative(path: &Path) -> String {
+    format!("./{}", name(path))
+}
+
+/// Readable copy of the note committed along with it, so that versions can be diffed
+fn text_path(note_path: &Path) -> PathBuf {
+    note_path.with_extension(TEXT_EXTENSION)
+}
+
+fn render(note: &InternalNote) -> String {
+    let mut text = format!("# {}\n\n", note.title);
+    for todo in &note.todos {
+        let todo = todo.0.read().unwrap();
+        let status = match todo.done {
+            Some(true) => "x",
+            Some(false) => " ",
+            None => "?",
+        };
+        text += &format!("- [{}] {}\n", status, todo.description);
+    }
+    text
+}
+
+/// Make the data directory a repository unless it is one already. Commits need an identity,
+/// one is set for this repository when git has none
+pub(super) fn init(folder: &Path) -> Result<()> {
+    if !folder.join(".git").exists() {
+        git(folder, &["init", "--quiet"])?;
+    }
+    if !run(folder, &["config", "user.email"])?.status.success() {
+        git(folder, &["config", "user.name", DEFAULT_NAME])?;
+        git(folder, &["config", "user.email", DEFAULT_EMAIL])?;
+    }
+    Ok(())
+}
+
+/// Stage the files as given, the removed ones included, and commit them unless nothing changed.
+/// Nothing else of the data directory is committed
+fn commit_files(folder: &Path, files: &[(String, Option<Vec<u8>>)], message: &str) -> Result<()> {
+    for (name, content) in files {
+        match content {
+            Some(content) => {
+                let hash = hash_object(folder, content)?;
+                git(
+                    folder,
+                    &[
+                        "update-index",
+                        "--add",
+                        "--cacheinfo",
+                        &format!("100644,{},{}", hash, name),
+                    ],
+                )?;
+            }
+            None => {
+                git(folder, &["update-index", "--force-remove", "--", name])?;
+            }
+        }
+    }
+    if run(folder, &["diff", "--cached", "--quiet"])?
+        .status
+        .success()
+    {
+        return Ok(());
+    }
+    // A signing prompt would break the terminal interface
+    git(
+        folder,
+        &["commit", "--quiet", "--no-gpg-sign", "--message", message],
+    )?;
+    Ok(())
+}
+
+pub(super) fn write_text(note_path: &Path, note: &InternalNote) -> Result<()> {
+    write(text_path(note_path), render(note)).context("Cannot write the text copy")
+}
+
+/// Note files with their text copies
+fn note_paths(note_paths: &[PathBuf]) -> Vec<PathBuf> {
+    note_paths
+        .iter()
+        .flat_map(|path| [path.clone(), text_path(path)])
+        .collect()
+}
+
+/// Commit the notes of `folder` as they are on disk, in the background
+pub(super) fn commit_all(folder: &Path, paths: &[PathBuf], message: &str) {
+    queue(folder, note_paths(paths), message);
+}
+
+/// Commit the note just written along with its text copy, in the background
+pub(super) fn commit(note_path: &Path, note: &InternalNote, message: &str) -> Result<()> {
+    write_text(note_path, note)?;
+    commit_all(folder(note_path), &[note_path.to_path_buf()], message);
+    Ok(())
+}
+
+/// Commit the removal of the note, its files are already gone but the text copy
+pub(super) fn remove(note_path: &Path, message: &str) {
+    let _ = remove_file(text_path(note_path));
+    commit_all(folder(note_path), &[note_path.to_path_buf()], message);
+}
+
+/// Commits changing the note, the latest first
+pub(super) fn versions(note_path: &Path) -> Result<Vec<Version>> {
+    flush();
+    let log = git(
+        folder(note_path),
+        &[
+            "log",
+            "--format=%h%x09%ad%x09%s",
+            &format!("--date={}", TIME_FORMAT),
+            "--",
+            &relative(note_path),
+        ],
+    )?;
+    Ok(String::from_utf8_lossy(&log)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            Some(Version {
+                id: fields.next()?.to_string(),
+                time: fields.next()?.to_string(),
+                summary: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Changes from `version` to the last saved content, in the text form
+pub(super) fn diff(note_path: &Path, version: &Version) -> Result<String> {
+    flush();
+    let diff = git(
+        folder(note_path),
+        &[
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            &version.id,
+            "--",
+            &relative(&text_path(note_path)),
+        ],
+    )?;
+    Ok(String::from_utf8_lossy(&diff).to_string())
+}
+
+/// Content of the note file at `version`
+pub(super) fn show(note_path: &Path, version: &Version) -> Result<Vec<u8>> {
+    flush();
+    git(
+        folder(note_path),
+        &["show", &format!("{}:{}", version.id, relative(note_path))],
+    )
+    .with_context(|| format!("No {} file in {}", FILE_EXTENSION, version.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::text_path;
+    use crate::backend::NotesWallBuilder;
+
+    #[test]
+    fn git_test() {
+        let folder = Path::new("/tmp/test_todotui_git");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .build()
+            .unwrap();
+        let old = wall.create_note_with("old", &[]).unwrap();
+        assert!(old.save().is_ok());
+
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .git(true)
+            .build()
+            .unwrap();
+        assert!(wall.init().is_ok());
+        assert!(folder.join(".git").exists());
+        // Notes written before the repository have a first version
+        let versions = wall.get_notes()[0].versions().unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].summary, "Track the notes");
+
+        let note = wall
+            .create_note_with("groceries", &["milk".into()])
+            .unwrap();
+        assert!(note.save().is_ok());
+        let milk = note.todos()[0].clone();
+        assert!(milk.set_done(Some(true)).is_ok());
+        assert!(note.save().is_ok());
+        // Nothing changed, no commit
+        assert!(note.save().is_ok());
+
+        let versions = note.versions().unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].summary, "Save \"groceries\"");
+        let diff = note.version_diff(&versions[1]).unwrap();
+        assert!(diff.contains("-- [ ] milk"));
+        assert!(diff.contains("+- [x] milk"));
+
+        assert!(note.restore(&versions[1]).is_ok());
+        assert_eq!(note.todos()[0].done().unwrap(), Some(false));
+        assert!(!note.dirty());
+        let restored = note.versions().unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored[0].summary, "Restore \"groceries\"");
+
+        let path = folder.join(note.file_name());
+        assert!(text_path(&path).exists());
+        assert!(wall.remove_note(&note).is_ok());
+        assert!(!text_path(&path).exists());
+        // The removal is committed, the versions stay in the repository
+        assert_eq!(super::versions(&path).unwrap().len(), 4);
+
+        let _ = fs::remove_dir_all(folder);
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    fs::{OpenOptions, read_to_string},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+
+use super::{InternalNote, InternalTodo, Todo};
+
+static HISTORY_EXTENSION: &str = "log";
+static TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Line of the activity log of a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub time: String,
+    pub text: String,
+}
+
+/// Note as last written, the changes logged at the next save are relative to it
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Snapshot {
+    title: String,
+    /// Handles keep the todos identity, the copies their content at that time
+    todos: Vec<(Todo, InternalTodo)>,
+}
+
+impl Snapshot {
+    pub(super) fn of(note: &InternalNote) -> Self {
+        Self {
+            title: note.title.clone(),
+            todos: note
+                .todos
+                .iter()
+                .map(|todo| (todo.clone(), todo.0.read().unwrap().clone()))
+                .collect(),
+        }
+    }
+}
+
+/// The activity log is kept next to the note file
+pub(super) fn path(note_path: &Path) -> PathBuf {
+    note_path.with_extension(HISTORY_EXTENSION)
+}
+
+/// What changed from `before` to `after`, `None` for a note never written
+pub(super) fn changes(before: Option<&Snapshot>, after: &InternalNote) -> Vec<String> {
+    let mut changes = Vec::new();
+    let empty = Snapshot {
+        title: after.title.clone(),
+        todos: Vec::new(),
+    };
+    let before = match before {
+        Some(before) => before,
+        None => {
+            changes.push(format!("Created \"{}\"", after.title));
+            &empty
+        }
+    };
+    if before.title != after.title {
+        changes.push(format!(
+            "Renamed \"{}\" to \"{}\"",
+            before.title, after.title
+        ));
+    }
+    for todo in &after.todos {
+        let current = todo.0.read().unwrap();
+        let Some((_, previous)) = before.todos.iter().find(|(other, _)| other.is(todo)) else {
+            changes.push(format!("Added \"{}\"", current.description));
+            continue;
+        };
+        if previous.description != current.description {
+            changes.push(format!(
+                "Edited \"{}\" to \"{}\"",
+                previous.description, current.description
+            ));
+        }
+        if previous.done != current.done {
+            changes.push(format!(
+                "Marked \"{}\" {}",
+                current.description,
+                match current.done {
+                    Some(true) => "done",
+                    Some(false) => "open",
+                    None => "unknown",
+                }
+            ));
+        }
+    }
+    for (todo, previous) in &before.todos {
+        if !after.todos.iter().any(|other| other.is(todo)) {
+            changes.push(format!("Removed \"{}\"", previous.description));
+        }
+    }
+    changes
+}
+
+pub(super) fn append(note_path: &Path, changes: &[String]) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let time = Local::now().format(TIME_FORMAT);
+    let lines: String = changes
+        .iter()
+        .map(|change| format!("{}\t{}\n", time, change.replace('\n', " ")))
+        .collect();
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path(note_path))
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .context("Cannot write the history")
+}
+
+/// Entries of the log, the oldest first. A note without history has none
+pub(super) fn read(note_path: &Path) -> Result<Vec<Entry>> {
+    let path = path(note_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(read_to_string(path)
+        .context("Cannot read the history")?
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(time, text)| Entry {
+            time: time.to_string(),
+            text: text.to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::path;
+    use crate::backend::NotesWallBuilder;
+
+    #[test]
+    fn history_test() {
+        let folder = Path::new("/tmp/test_todotui_history");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .build()
+            .unwrap();
+        let mut note = wall
+            .create_note_with("groceries", &["milk".into()])
+            .unwrap();
+        let note_path = folder.join("note.post");
+        assert!(note.set_path(note_path.clone()).is_ok());
+        assert!(note.save().is_ok());
+
+        assert!(note.set_title("shopping").is_ok());
+        let milk = note.todos()[0].clone();
+        assert!(milk.set_description("oat milk").is_ok());
+        assert!(milk.set_done(Some(true)).is_ok());
+        let eggs = note.create_todo().unwrap();
+        assert!(eggs.set_description("eggs").is_ok());
+        assert!(note.save().is_ok());
+        // Nothing changed
+        assert!(note.save().is_ok());
+        assert!(note.remove_todo(&milk).is_ok());
+        assert!(note.save().is_ok());
+
+        let texts: Vec<String> = note
+            .history()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.text)
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Created \"groceries\"",
+                "Added \"milk\"",
+                "Renamed \"groceries\" to \"shopping\"",
+                "Edited \"milk\" to \"oat milk\"",
+                "Marked \"oat milk\" done",
+                "Added \"eggs\"",
+                "Removed \"oat milk\"",
+            ]
+        );
+
+        assert!(wall.remove_note(&note).is_ok());
+        assert!(!path(&note_path).exists());
+
+        let _ = fs::remove_dir_all(folder);
+    }
+}
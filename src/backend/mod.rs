@@ -1,28 +1,83 @@
 use std::{
-    fs::{read, read_dir, remove_file, write},
+    cmp::{Ordering, Reverse},
+    collections::HashSet,
+    fs::{copy, metadata, read, read_dir, read_to_string, remove_file, rename, write},
     hash::Hash,
     ops::Deref,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
 };
 
-use anyhow::{Context, Result, bail, ensure};
-use chrono::Utc;
+use anyhow::{Context, Error, Result, bail, ensure};
+use chrono::{DateTime, Local, Utc};
 use derive_builder::Builder;
 use postcard::{from_bytes, to_stdvec};
 use serde::{Deserialize, Serialize};
 
+use crate::SortMode;
+
+pub use git::Version;
+pub use history::Entry;
+pub use template::Template;
+
+mod git;
+mod history;
+mod template;
+
 static DATE_FORMAT: &str = "%d_%m_%Y_%H:%M_%6f";
 static FILE_EXTENSION: &str = "post";
 static POISONED: &str = "Poisoned mutex";
 static EMPTY_NOTE: &str = "Note is empty";
 static FAILED_SERIALIZATION: &str = "Failed to serialize";
+static FAILED_DESERIALIZATION: &str = "Failed to deserialize";
 static FAILED_REMOVE: &str = "Failed to remove";
+/// File names of the notes in the order chosen by the user, one per line
+static INDEX_FILE: &str = "index";
+/// Saved files start with it followed by the format version, older files have neither
+static MAGIC: &[u8] = b"todotui";
+const FORMAT_VERSION: u8 = 4;
 
-#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct InternalTodo {
     done: Option<bool>,
     description: String,
+    /// Unix timestamps, missing for the todos saved before they were recorded
+    created: Option<i64>,
+    completed: Option<i64>,
+}
+
+impl Default for InternalTodo {
+    fn default() -> Self {
+        Self {
+            done: Default::default(),
+            description: Default::default(),
+            created: Some(Utc::now().timestamp()),
+            completed: None,
+        }
+    }
+}
+
+/// Layout of the todos up to version 3, without timestamps
+#[derive(Deserialize, Serialize, Default)]
+struct InternalTodoV3 {
+    done: Option<bool>,
+    description: String,
+}
+
+impl From<InternalTodoV3> for Todo {
+    fn from(todo: InternalTodoV3) -> Self {
+        Todo(Arc::new(RwLock::new(InternalTodo {
+            done: todo.done,
+            description: todo.description,
+            created: None,
+            completed: None,
+        })))
+    }
+}
+
+fn upgrade(todos: Vec<InternalTodoV3>) -> Vec<Todo> {
+    todos.into_iter().map(Todo::from).collect()
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -55,13 +110,38 @@ impl Todo {
         Ok(lock.unwrap().done)
     }
 
+    /// Becoming done records the completion time, any other status forgets it
     pub fn set_done(&self, done: Option<bool>) -> Result<()> {
         let lock = self.0.write();
         ensure!(lock.is_ok(), POISONED);
-        lock.unwrap().done = done;
+        let mut todo = lock.unwrap();
+        todo.completed = match done {
+            Some(true) if todo.done == Some(true) => todo.completed,
+            Some(true) => Some(Utc::now().timestamp()),
+            _ => None,
+        };
+        todo.done = done;
         Ok(())
     }
 
+    pub fn created(&self) -> Result<Option<DateTime<Utc>>> {
+        let lock = self.0.read();
+        ensure!(lock.is_ok(), POISONED);
+        Ok(lock
+            .unwrap()
+            .created
+            .and_then(|created| DateTime::from_timestamp(created, 0)))
+    }
+
+    pub fn completed(&self) -> Result<Option<DateTime<Utc>>> {
+        let lock = self.0.read();
+        ensure!(lock.is_ok(), POISONED);
+        Ok(lock
+            .unwrap()
+            .completed
+            .and_then(|completed| DateTime::from_timestamp(completed, 0)))
+    }
+
     pub fn description(&self) -> Result<String> {
         let lock = self.0.read();
         ensure!(lock.is_ok(), POISONED);
@@ -74,6 +154,18 @@ impl Todo {
         lock.unwrap().description = description.to_string();
         Ok(())
     }
+
+    /// Whether both handles share the same todo, equality only compares the content
+    pub fn is(&self, other: &Todo) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Deep copy not sharing the underlying data with `self`
+    pub fn duplicate(&self) -> Result<Todo> {
+        let lock = self.0.read();
+        ensure!(lock.is_ok(), POISONED);
+        Ok(Todo(Arc::new(RwLock::new(lock.unwrap().clone()))))
+    }
 }
 
 #[derive(Eq, Clone, Deserialize, Serialize, Debug, PartialOrd)]
@@ -81,6 +173,9 @@ struct InternalNote {
     title: String,
     created: String,
     todos: Vec<Todo>,
+    hide_completed: bool,
+    archived: bool,
+    pinned: bool,
 }
 
 impl Default for InternalNote {
@@ -90,6 +185,99 @@ impl Default for InternalNote {
             title: Default::default(),
             created,
             todos: Default::default(),
+            hide_completed: false,
+            archived: false,
+            pinned: false,
+        }
+    }
+}
+
+/// Layout of the files saved before the format was versioned
+#[derive(Deserialize, Serialize)]
+struct InternalNoteV0 {
+    title: String,
+    created: String,
+    todos: Vec<InternalTodoV3>,
+}
+
+impl From<InternalNoteV0> for InternalNote {
+    fn from(note: InternalNoteV0) -> Self {
+        Self {
+            title: note.title,
+            created: note.created,
+            todos: upgrade(note.todos),
+            hide_completed: false,
+            archived: false,
+            pinned: false,
+        }
+    }
+}
+
+/// Layout of the version 1 files, before notes could be archived
+#[derive(Deserialize, Serialize)]
+struct InternalNoteV1 {
+    title: String,
+    created: String,
+    todos: Vec<InternalTodoV3>,
+    hide_completed: bool,
+}
+
+impl From<InternalNoteV1> for InternalNote {
+    fn from(note: InternalNoteV1) -> Self {
+        Self {
+            title: note.title,
+            created: note.created,
+            todos: upgrade(note.todos),
+            hide_completed: note.hide_completed,
+            archived: false,
+            pinned: false,
+        }
+    }
+}
+
+/// Layout of the version 2 files, before notes could be pinned
+#[derive(Deserialize, Serialize)]
+struct InternalNoteV2 {
+    title: String,
+    created: String,
+    todos: Vec<InternalTodoV3>,
+    hide_completed: bool,
+    archived: bool,
+}
+
+impl From<InternalNoteV2> for InternalNote {
+    fn from(note: InternalNoteV2) -> Self {
+        Self {
+            title: note.title,
+            created: note.created,
+            todos: upgrade(note.todos),
+            hide_completed: note.hide_completed,
+            archived: note.archived,
+            pinned: false,
+        }
+    }
+}
+
+/// Layout of the version 3 files, before the todos had timestamps
+#[derive(Deserialize, Serialize)]
+struct InternalNoteV3 {
+    title: String,
+    created: String,
+    todos: Vec<InternalTodoV3>,
+    hide_completed: bool,
+    archived: bool,
+    pinned: bool,
+}
+
+impl From<InternalNoteV3> for InternalNote {
+    fn from(note: InternalNoteV3) -> Self {
+        Self {
+            title: note.title,
+            created: note.created,
+            todos: upgrade(note.todos),
+            hide_completed: note.hide_completed,
+            archived: note.archived,
+            pinned: note.pinned,
         }
     }
 }
@@ -108,22 +296,61 @@ impl Hash for InternalNote {
 }
 
 impl InternalNote {
+    fn decode(data: &[u8]) -> Result<Self> {
+        match data.strip_prefix(MAGIC) {
+            Some([FORMAT_VERSION, data @ ..]) => Ok(from_bytes(data)?),
+            Some([1, data @ ..]) => Ok(from_bytes::<InternalNoteV1>(data)?.into()),
+            Some([2, data @ ..]) => Ok(from_bytes::<InternalNoteV2>(data)?.into()),
+            Some([3, data @ ..]) => Ok(from_bytes::<InternalNoteV3>(data)?.into()),
+            Some([version, ..]) => bail!("Unsupported format version {}", version),
+            Some([]) => bail!(FAILED_DESERIALIZATION),
+            None => Ok(from_bytes::<InternalNoteV0>(data)?.into()),
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut data = MAGIC.to_vec();
+        data.push(FORMAT_VERSION);
+        data.extend(to_stdvec(self)?);
+        Ok(data)
+    }
+
     fn add_todo(&mut self, todo: Todo) {
         self.todos.push(todo)
     }
 
+    fn insert_todo(&mut self, index: usize, todo: Todo) {
+        self.todos.insert(index.min(self.todos.len()), todo)
+    }
+
+    /// Pasted copies are equal to their original, the very todo is removed
     fn remove_todo(&mut self, todo: &Todo) {
-        let index = self.todos.iter().position(|e| e == todo);
+        let index = self.todos.iter().position(|e| e.is(todo));
         if let Some(index) = index {
             self.todos.remove(index);
         }
     }
+
+    fn move_todo(&mut self, from: usize, to: usize) {
+        if from < self.todos.len() {
+            let todo = self.todos.remove(from);
+            self.insert_todo(to, todo);
+        }
+    }
 }
 
 #[derive(Debug, Eq)]
 struct PersistenceInternalNote {
     path: PathBuf,
     note: Option<InternalNote>,
+    /// The file is missing or older than the data in memory
+    dirty: bool,
+    /// Last time the file was written, `None` until the note is saved
+    modified: Option<SystemTime>,
+    /// Content of the file, `None` until it is loaded or written
+    snapshot: Option<history::Snapshot>,
+    /// Whether writes are committed to the repository of the data directory
+    git: bool,
 }
 
 impl Default for PersistenceInternalNote {
@@ -131,6 +358,37 @@ impl Default for PersistenceInternalNote {
         Self {
             path: Default::default(),
             note: Some(Default::default()),
+            dirty: true,
+            modified: None,
+            snapshot: None,
+            git: false,
+        }
+    }
+}
+
+impl PersistenceInternalNote {
+    /// The file has just been written with the data in memory, the changes since the last write
+    /// are appended to the history
+    fn saved(&mut self) {
+        let changes = self.note.as_ref().map_or(Vec::new(), |note| {
+            history::changes(self.snapshot.as_ref(), note)
+        });
+        self.recorded("Save", &changes);
+    }
+
+    /// Like `saved` with the given changes, committed under `action` when the data directory is a
+    /// repository
+    fn recorded(&mut self, action: &str, changes: &[String]) {
+        self.dirty = false;
+        self.modified = Some(SystemTime::now());
+        if let Some(note) = &self.note {
+            // The history and the commits are best effort, the note itself is saved
+            let _ = history::append(&self.path, changes);
+            if self.git {
+                let message = format!("{} \"{}\"\n\n{}", action, note.title, changes.join("\n"));
+                let _ = git::commit(&self.path, note, &message);
+            }
+            self.snapshot = Some(history::Snapshot::of(note));
         }
     }
 }
@@ -188,6 +446,13 @@ impl Note {
         Ok(())
     }
 
+    fn set_git(&self, git: bool) -> Result<()> {
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        lock.unwrap().git = git;
+        Ok(())
+    }
+
     pub fn set_title(&self, title: &str) -> Result<()> {
         let lock = self.0.write();
         ensure!(lock.is_ok(), POISONED);
@@ -241,6 +506,18 @@ impl Note {
         }
     }
 
+    pub fn insert_todo(&mut self, index: usize, todo: Todo) -> Result<()> {
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        match lock.unwrap().note.as_mut() {
+            Some(note) => {
+                note.insert_todo(index, todo);
+                Ok(())
+            }
+            None => bail!(EMPTY_NOTE),
+        }
+    }
+
     pub fn remove_todo(&mut self, todo: &Todo) -> Result<()> {
         let lock = self.0.write();
         ensure!(lock.is_ok(), POISONED);
@@ -253,6 +530,93 @@ impl Note {
         }
     }
 
+    /// Move the todo at `from` to `to`, shifting the ones in between
+    pub fn move_todo(&mut self, from: usize, to: usize) -> Result<()> {
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        match lock.unwrap().note.as_mut() {
+            Some(note) => {
+                note.move_todo(from, to);
+                Ok(())
+            }
+            None => bail!(EMPTY_NOTE),
+        }
+    }
+
+    /// Whether the done todos are left out of the list
+    pub fn hide_completed(&self) -> bool {
+        self.0
+            .read()
+            .is_ok_and(|data| data.note.as_ref().is_some_and(|note| note.hide_completed))
+    }
+
+    pub fn set_hide_completed(&self, hide_completed: bool) -> Result<()> {
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        match lock.unwrap().note.as_mut() {
+            Some(note) => {
+                note.hide_completed = hide_completed;
+                Ok(())
+            }
+            None => bail!(EMPTY_NOTE),
+        }
+    }
+
+    pub fn archived(&self) -> bool {
+        self.0
+            .read()
+            .is_ok_and(|data| data.note.as_ref().is_some_and(|note| note.archived))
+    }
+
+    pub fn set_archived(&self, archived: bool) -> Result<()> {
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        match lock.unwrap().note.as_mut() {
+            Some(note) => {
+                note.archived = archived;
+                Ok(())
+            }
+            None => bail!(EMPTY_NOTE),
+        }
+    }
+
+    pub fn pinned(&self) -> bool {
+        self.0
+            .read()
+            .is_ok_and(|data| data.note.as_ref().is_some_and(|note| note.pinned))
+    }
+
+    pub fn set_pinned(&self, pinned: bool) -> Result<()> {
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        match lock.unwrap().note.as_mut() {
+            Some(note) => {
+                note.pinned = pinned;
+                Ok(())
+            }
+            None => bail!(EMPTY_NOTE),
+        }
+    }
+
+    /// Every todo is done, the last one at least `delay` ago. Saving the note doesn't matter, and
+    /// a note whose todos were all done before the completion times were recorded is kept
+    fn finished_for(&self, delay: Duration) -> bool {
+        let todos = self.todos();
+        let last_completed = todos
+            .iter()
+            .filter_map(|todo| todo.completed().ok().flatten())
+            .max();
+        !todos.is_empty()
+            && todos
+                .iter()
+                .all(|todo| todo.done().is_ok_and(|done| done == Some(true)))
+            && last_completed.is_some_and(|completed| {
+                (Utc::now() - completed)
+                    .to_std()
+                    .is_ok_and(|elapsed| elapsed >= delay)
+            })
+    }
+
     pub fn todos(&self) -> Vec<Todo> {
         self.0.read().map_or(Vec::new(), |data| match &data.note {
             Some(data) => data.todos.to_vec(),
@@ -262,12 +626,16 @@ impl Note {
 
     fn load(&self) -> bool {
         let path = self.0.read().unwrap().path.clone();
+        let modified = metadata(&path).and_then(|file| file.modified()).ok();
         read(path)
-            .map(|data| from_bytes::<InternalNote>(&data))
+            .map(|data| InternalNote::decode(&data))
             .is_ok_and(|note| match note {
                 Ok(note) => match self.0.write() {
                     Ok(mut data) => {
+                        data.snapshot = Some(history::Snapshot::of(&note));
                         data.note = Some(note);
+                        data.dirty = false;
+                        data.modified = modified;
                         true
                     }
                     Err(_) => false,
@@ -277,28 +645,209 @@ impl Note {
     }
 
     pub fn save(&self) -> Result<()> {
-        let lock = self.0.read().unwrap();
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        let mut lock = lock.unwrap();
 
-        lock.note
+        let result = lock
+            .note
             .as_ref()
-            .map_or(Ok(()), |note| match to_stdvec(&note) {
+            .map_or(Ok(()), |note| match note.encode() {
                 std::result::Result::Ok(data) => {
                     write(lock.path.clone(), data).context(FAILED_SERIALIZATION)
                 }
                 Err(_) => bail!(FAILED_SERIALIZATION),
-            })
+            });
+        match result.is_ok() {
+            true => lock.saved(),
+            false => lock.dirty = true,
+        }
+        result
+    }
+
+    fn sort_key(&self) -> SortKey {
+        let todos = self.todos();
+        SortKey {
+            title: self.title().unwrap_or_default().to_lowercase(),
+            created: created_key(&self.created().unwrap_or_default()),
+            modified: self.0.read().ok().and_then(|data| data.modified),
+            open: todos
+                .iter()
+                .filter(|todo| todo.done().is_ok_and(|done| done == Some(false)))
+                .count(),
+            file_name: self.file_name(),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        self.0.read().map_or(String::new(), |data| {
+            data.path
+                .file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().to_string())
+        })
+    }
+
+    /// Activity log of the note, the oldest entry first
+    pub fn history(&self) -> Result<Vec<Entry>> {
+        let path = self.0.read().map(|data| data.path.clone());
+        ensure!(path.is_ok(), POISONED);
+        history::read(&path.unwrap())
+    }
+
+    /// Committed versions of the note, the latest first
+    pub fn versions(&self) -> Result<Vec<Version>> {
+        let path = self.git_path()?;
+        git::versions(&path)
+    }
+
+    /// Changes made since `version`, as a diff of the text copies
+    pub fn version_diff(&self, version: &Version) -> Result<String> {
+        let path = self.git_path()?;
+        git::diff(&path, version)
+    }
+
+    /// Replace the note with its content at `version` and save it
+    pub fn restore(&self, version: &Version) -> Result<()> {
+        let path = self.git_path()?;
+        let note = InternalNote::decode(&git::show(&path, version)?)?;
+        let data = note.encode()?;
+        let lock = self.0.write();
+        ensure!(lock.is_ok(), POISONED);
+        let mut lock = lock.unwrap();
+        write(&path, data).context(FAILED_SERIALIZATION)?;
+        lock.note = Some(note);
+        lock.recorded(
+            "Restore",
+            &[format!("Restored the version of {}", version.time)],
+        );
+        Ok(())
+    }
+
+    /// Path of the note file, only once the data directory is a repository
+    fn git_path(&self) -> Result<PathBuf> {
+        let data = self.0.read();
+        ensure!(data.is_ok(), POISONED);
+        let data = data.unwrap();
+        ensure!(
+            data.git,
+            "The version history needs git = true in config.toml"
+        );
+        Ok(data.path.clone())
+    }
+
+    /// Whether the last changes haven't reached the disk yet
+    pub fn dirty(&self) -> bool {
+        self.0.read().map_or(true, |data| data.dirty)
+    }
+}
+
+/// Values the notes are sorted by, read once per sort
+struct SortKey {
+    title: String,
+    created: String,
+    modified: Option<SystemTime>,
+    open: usize,
+    file_name: String,
+}
+
+impl SortKey {
+    fn cmp(&self, other: &Self, sort: SortMode) -> Ordering {
+        let primary = match sort {
+            SortMode::Manual | SortMode::Created => Ordering::Equal,
+            // Never saved notes are the most recent ones
+            SortMode::Modified => Reverse((self.modified.is_none(), self.modified))
+                .cmp(&Reverse((other.modified.is_none(), other.modified))),
+            SortMode::Title => self.title.cmp(&other.title),
+            SortMode::OpenTodos => other.open.cmp(&self.open),
+        };
+        primary
+            .then_with(|| self.created.cmp(&other.created))
+            .then_with(|| self.file_name.cmp(&other.file_name))
+    }
+}
+
+/// Write every file or none of them: they go to temporary files first, renamed once all are written.
+/// The replaced files are backed up until then, a failed rename puts back the ones already renamed
+fn write_all(files: &[(&Path, Vec<u8>)]) -> Result<()> {
+    let temporary = |path: &Path| path.with_extension("tmp");
+    let backup = |path: &Path| path.with_extension("bak");
+    for (index, (path, data)) in files.iter().enumerate() {
+        if let Err(err) = write(temporary(path), data) {
+            for (path, _) in &files[..=index] {
+                let _ = remove_file(temporary(path));
+            }
+            return Err(err).context(FAILED_SERIALIZATION);
+        }
+    }
+    // Whether each renamed file replaced one, backed up
+    let mut replaced = Vec::with_capacity(files.len());
+    for (index, (path, _)) in files.iter().enumerate() {
+        let exists = path.exists();
+        let result = match exists {
+            true => copy(path, backup(path)).map(|_| ()),
+            false => Ok(()),
+        }
+        .and_then(|_| rename(temporary(path), path));
+        if let Err(err) = result {
+            for ((path, _), backed_up) in files.iter().zip(&replaced) {
+                let _ = match backed_up {
+                    true => rename(backup(path), path),
+                    false => remove_file(path),
+                };
+            }
+            for (path, _) in &files[index..] {
+                let _ = remove_file(temporary(path));
+            }
+            if exists {
+                let _ = remove_file(backup(path));
+            }
+            return Err(err).context(FAILED_SERIALIZATION);
+        }
+        replaced.push(exists);
+    }
+    for ((path, _), backed_up) in files.iter().zip(&replaced) {
+        if *backed_up {
+            let _ = remove_file(backup(path));
+        }
+    }
+    Ok(())
+}
+
+/// `DATE_FORMAT` rearranged to sort chronologically
+fn created_key(created: &str) -> String {
+    match created.split('_').collect::<Vec<_>>()[..] {
+        [day, month, year, time, fraction] => {
+            format!("{}{}{}{}{}", year, month, day, time, fraction)
+        }
+        _ => created.to_string(),
     }
 }
 
 #[derive(Builder, Default)]
 pub struct NotesWall {
     folder_path: PathBuf,
+    /// Notes in manual order, `get_notes` sorts them
     #[builder(setter(skip))]
     notes: Vec<Note>,
+    #[builder(default)]
+    sort: SortMode,
+    /// Whether `get_notes` lists the archived notes instead of the others
+    #[builder(setter(skip))]
+    archive: bool,
+    /// Finished notes are archived at start up this long after their last todo was done
+    #[builder(default)]
+    archive_after: Option<Duration>,
+    /// Commit every save and removal to a git repository in the data directory
+    #[builder(default)]
+    git: bool,
 }
 
 impl NotesWall {
     pub fn init(&mut self) -> Result<()> {
+        if self.git {
+            git::init(&self.folder_path)
+                .context("Cannot use the data directory as a repository")?;
+        }
         self.notes = read_dir(self.folder_path.as_path())?
             .filter(|file| {
                 file.as_ref().is_ok_and(|f| {
@@ -311,18 +860,160 @@ impl NotesWall {
             .map(|path| {
                 let note = Note::default();
                 let _ = note.set_path(path.unwrap().path());
+                let _ = note.set_git(self.git);
                 note
             })
             .filter(|note| note.load())
             .collect();
+        // read_dir order depends on the file system
+        self.notes.sort_by_key(|note| note.file_name());
+        self.apply_index();
+        if self.git {
+            self.track()?;
+        }
+        self.auto_archive();
+        Ok(())
+    }
+
+    /// Commit the notes as they are on disk, with their text copies, so that each has a version
+    /// even when written before the repository existed
+    fn track(&self) -> Result<()> {
+        let mut paths = Vec::new();
+        for note in &self.notes {
+            let data = note.0.read();
+            ensure!(data.is_ok(), POISONED);
+            let data = data.unwrap();
+            if let Some(internal) = &data.note {
+                git::write_text(&data.path, internal)?;
+                paths.push(data.path.clone());
+            }
+        }
+        git::commit_all(&self.folder_path, &paths, "Track the notes");
         Ok(())
     }
 
+    /// Archive the notes finished for `archive_after`, those failing to save stay dirty
+    fn auto_archive(&self) {
+        let Some(delay) = self.archive_after else {
+            return;
+        };
+        for note in &self.notes {
+            if !note.archived() && note.finished_for(delay) && note.set_archived(true).is_ok() {
+                let _ = note.save();
+            }
+        }
+    }
+
+    /// Notes listed in the index file come first in its order, the others follow
+    fn apply_index(&mut self) {
+        let Ok(index) = read_to_string(self.folder_path.join(INDEX_FILE)) else {
+            return;
+        };
+        let names: Vec<&str> = index.lines().collect();
+        self.notes.sort_by_key(|note| {
+            let name = note.file_name();
+            names
+                .iter()
+                .position(|entry| *entry == name)
+                .unwrap_or(names.len())
+        });
+    }
+
+    fn save_index(&self) -> Result<()> {
+        let index: String = self
+            .notes
+            .iter()
+            .map(|note| note.file_name() + "\n")
+            .collect();
+        write(self.folder_path.join(INDEX_FILE), index).context(FAILED_SERIALIZATION)
+    }
+
+    /// Move the note at `from` to `to` of the current view and save the new order, only in manual
+    /// order
+    pub fn move_note(&mut self, from: usize, to: usize) -> Result<()> {
+        ensure!(
+            self.sort == SortMode::Manual,
+            "Notes can only be moved in manual order"
+        );
+        let visible = self.get_notes();
+        ensure!(from < visible.len(), "No note at {}", from);
+        let (note, target) = (&visible[from], &visible[to.min(visible.len() - 1)]);
+        ensure!(
+            note.pinned() == target.pinned(),
+            "Pinned notes stay above the others"
+        );
+        self.notes.retain(|other| other != note);
+        // Positions are in the current view, the notes of the other view keep their place
+        let position = self.notes.iter().position(|other| other == target).map_or(
+            self.notes.len(),
+            |position| match from < to {
+                true => position + 1,
+                false => position,
+            },
+        );
+        self.notes.insert(position, note.clone());
+        self.save_index()
+    }
+
+    /// Notes of the current view, the archived ones or the others. Pinned notes come first
+    /// whatever the order
     pub fn get_notes(&self) -> Vec<Note> {
+        self.notes_in(self.archive)
+    }
+
+    /// Notes of either view in the order `get_notes` lists them once that view is shown
+    pub fn notes_in(&self, archive: bool) -> Vec<Note> {
+        let notes = self.notes.iter().filter(|note| note.archived() == archive);
+        let mut notes: Vec<Note> = match self.sort {
+            SortMode::Manual => notes.cloned().collect(),
+            sort => {
+                let mut notes: Vec<(SortKey, Note)> =
+                    notes.map(|note| (note.sort_key(), note.clone())).collect();
+                notes.sort_by(|(a, _), (b, _)| a.cmp(b, sort));
+                notes.into_iter().map(|(_, note)| note).collect()
+            }
+        };
+        // Stable, both groups keep their order
+        notes.sort_by_key(|note| !note.pinned());
+        notes
+    }
+
+    /// Wait for the commits still running in the background, before quitting
+    pub fn wait_for_commits(&self) {
+        if self.git {
+            git::flush();
+        }
+    }
+
+    /// Every note, archived or not, in manual order
+    pub fn all_notes(&self) -> Vec<Note> {
         self.notes.to_vec()
     }
 
+    pub fn archive(&self) -> bool {
+        self.archive
+    }
+
+    pub fn set_archive(&mut self, archive: bool) {
+        self.archive = archive;
+    }
+
+    pub fn sort(&self) -> SortMode {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: SortMode) {
+        self.sort = sort;
+    }
+
     pub fn create_note(&mut self) -> Note {
+        let note = self.new_note();
+        self.notes.push(note.clone());
+        note
+    }
+
+    /// Empty note with its path in the data directory, not part of the wall yet
+    fn new_note(&self) -> Note {
         let note = Note::default();
         let mut path = self.folder_path.clone();
         path.push(
@@ -337,7 +1028,7 @@ impl NotesWall {
         );
         path.set_extension(FILE_EXTENSION);
         let _ = note.set_path(path);
-        self.notes.push(note.clone());
+        let _ = note.set_git(self.git);
         note
     }
 
@@ -351,10 +1042,201 @@ impl NotesWall {
                     let data_guard = note.0.read().unwrap();
                     data_guard.path.clone()
                 };
-                remove_file(path.as_path()).context(FAILED_REMOVE)
+                // A note without history has no log to remove
+                let _ = remove_file(history::path(&path));
+                remove_file(path.as_path()).context(FAILED_REMOVE)?;
+                if self.git {
+                    let message = format!("Remove \"{}\"", note.title().unwrap_or_default());
+                    git::remove(&path, &message);
+                }
+                Ok(())
             })
     }
 
+    /// Templates of the data directory sorted by name, with the errors of the invalid ones
+    pub fn templates(&self) -> Result<(Vec<Template>, Vec<Error>)> {
+        Template::load_all(&self.folder_path)
+    }
+
+    /// Save the title and the todos of `note` as a template named after its title
+    pub fn save_template(&self, note: &Note) -> Result<()> {
+        let title = note.title()?;
+        let todos = note
+            .todos()
+            .iter()
+            .map(Todo::description)
+            .collect::<Result<_>>()?;
+        Template {
+            name: title.clone(),
+            title,
+            todos,
+        }
+        .save(&self.folder_path)
+    }
+
+    /// New note with the given title and open todos, not saved yet
+    pub fn create_note_with(&mut self, title: &str, todos: &[String]) -> Result<Note> {
+        let mut note = self.create_note();
+        note.set_title(title)?;
+        for description in todos {
+            let todo = note.create_todo()?;
+            todo.set_description(description)?;
+            todo.set_done(Some(false))?;
+        }
+        Ok(note)
+    }
+
+    pub fn create_note_from_template(&mut self, template: &Template) -> Result<Note> {
+        let (title, todos) = template.render(&Local::now().format("%Y-%m-%d").to_string());
+        self.create_note_with(&title, &todos)
+    }
+
+    /// Copy of `note` with every todo open again
+    pub fn duplicate_note(&mut self, note: &Note) -> Result<Note> {
+        let todos: Vec<String> = note
+            .todos()
+            .iter()
+            .map(Todo::description)
+            .collect::<Result<_>>()?;
+        self.create_note_with(&format!("{} (copy)", note.title()?), &todos)
+    }
+
+    /// Move, or copy, the todos at `indices` of `source` to the end of `target`, in their order.
+    /// Both notes are saved together and on failure neither the files nor the notes change
+    pub fn transfer_todos(
+        &self,
+        source: &Note,
+        indices: &[usize],
+        target: &Note,
+        copy: bool,
+    ) -> Result<()> {
+        ensure!(source != target, "The items are already in this note");
+        ensure!(
+            self.notes.contains(source) && self.notes.contains(target),
+            "Unknown note"
+        );
+        let (source_lock, target_lock) = (source.0.write(), target.0.write());
+        ensure!(source_lock.is_ok() && target_lock.is_ok(), POISONED);
+        let (mut source_lock, mut target_lock) = (source_lock.unwrap(), target_lock.unwrap());
+        let (Some(mut source_note), Some(mut target_note)) =
+            (source_lock.note.clone(), target_lock.note.clone())
+        else {
+            bail!(EMPTY_NOTE)
+        };
+        ensure!(
+            indices.iter().all(|index| *index < source_note.todos.len()),
+            "No item at {:?}",
+            indices
+        );
+
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        for index in &indices {
+            let todo = &source_note.todos[*index];
+            target_note.add_todo(match copy {
+                true => todo.duplicate()?,
+                false => todo.clone(),
+            });
+        }
+        if !copy {
+            for index in indices.iter().rev() {
+                source_note.todos.remove(*index);
+            }
+        }
+        let mut files = vec![(target_lock.path.as_path(), target_note.encode()?)];
+        if !copy {
+            files.push((source_lock.path.as_path(), source_note.encode()?));
+        }
+        write_all(&files)?;
+
+        if !copy {
+            source_lock.note = Some(source_note);
+            source_lock.saved();
+        }
+        target_lock.note = Some(target_note);
+        target_lock.saved();
+        Ok(())
+    }
+
+    /// Append the todos of `source` to `target` keeping one todo per description, then delete
+    /// `source`. Nothing changes when `target` can't be saved
+    pub fn merge_notes(&mut self, target: &Note, source: &Note) -> Result<()> {
+        ensure!(target != source, "Cannot merge a note into itself");
+        ensure!(
+            self.notes.contains(source) && self.notes.contains(target),
+            "Unknown note"
+        );
+        {
+            let lock = target.0.write();
+            ensure!(lock.is_ok(), POISONED);
+            let mut lock = lock.unwrap();
+            let Some(mut merged) = lock.note.clone() else {
+                bail!(EMPTY_NOTE)
+            };
+            let mut descriptions = HashSet::new();
+            let mut todos = Vec::new();
+            // The target keeps its very todos, only the ones coming from `source` are copied
+            for todo in &merged.todos {
+                if descriptions.insert(todo.description()?) {
+                    todos.push(todo.clone());
+                }
+            }
+            for todo in source.todos() {
+                if descriptions.insert(todo.description()?) {
+                    todos.push(todo.duplicate()?);
+                }
+            }
+            merged.todos = todos;
+            write_all(&[(lock.path.as_path(), merged.encode()?)])?;
+            lock.note = Some(merged);
+            lock.saved();
+        }
+        self.remove_note(source)
+    }
+
+    /// Move the todos at `indices` of `note` to a new note, both notes are saved together and on
+    /// failure nothing changes
+    pub fn split_note(&mut self, note: &Note, indices: &[usize]) -> Result<Note> {
+        ensure!(!indices.is_empty(), "No item to split");
+        ensure!(self.notes.contains(note), "Unknown note");
+        let split = self.new_note();
+        split.set_title(&format!("{} (split)", note.title()?))?;
+        {
+            let (lock, split_lock) = (note.0.write(), split.0.write());
+            ensure!(lock.is_ok() && split_lock.is_ok(), POISONED);
+            let (mut lock, mut split_lock) = (lock.unwrap(), split_lock.unwrap());
+            let (Some(mut remaining), Some(mut part)) =
+                (lock.note.clone(), split_lock.note.clone())
+            else {
+                bail!(EMPTY_NOTE)
+            };
+            ensure!(
+                indices.iter().all(|index| *index < remaining.todos.len()),
+                "No item at {:?}",
+                indices
+            );
+            let (moved, kept) = remaining
+                .todos
+                .into_iter()
+                .enumerate()
+                .partition::<Vec<_>, _>(|(index, _)| indices.contains(index));
+            remaining.todos = kept.into_iter().map(|(_, todo)| todo).collect();
+            part.todos = moved.into_iter().map(|(_, todo)| todo).collect();
+            write_all(&[
+                (lock.path.as_path(), remaining.encode()?),
+                (split_lock.path.as_path(), part.encode()?),
+            ])?;
+            lock.note = Some(remaining);
+            lock.saved();
+            split_lock.note = Some(part);
+            split_lock.saved();
+        }
+        self.notes.push(split.clone());
+        Ok(split)
+    }
+
+    #[cfg(test)]
     fn save_all(&self) -> Result<()> {
         let mut status = Ok(());
         for e in self.notes.iter() {
@@ -367,9 +1249,18 @@ impl NotesWall {
 
 #[cfg(test)]
 mod tests {
-    use std::{fs, path::Path};
+    use std::{fs, path::Path, time::Duration};
 
-    use crate::backend::NotesWallBuilder;
+    use chrono::Utc;
+    use postcard::to_stdvec;
+
+    use crate::{
+        SortMode,
+        backend::{
+            FILE_EXTENSION, InternalNoteV0, InternalNoteV1, InternalNoteV2, InternalTodoV3, MAGIC,
+            Note, NotesWall, NotesWallBuilder, Todo,
+        },
+    };
 
     static TEST_FOLDER_PATH: &str = "/tmp/test_todotui";
 
@@ -384,6 +1275,8 @@ mod tests {
     }
 
     #[test]
+    // `wall_2` is never loaded here, `dirty_test` reads a saved wall back
+    #[allow(unused_variables)]
     fn standard_test() {
         //Create work dir
         init_test_folder();
@@ -433,4 +1326,418 @@ mod tests {
 
         assert!(wall_1.save_all().is_ok());
     }
+
+    #[test]
+    fn dirty_test() {
+        let folder = Path::new("/tmp/test_todotui_dirty");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let new_wall = || {
+            NotesWallBuilder::default()
+                .folder_path(folder.to_path_buf())
+                .build()
+                .unwrap()
+        };
+
+        let mut wall = new_wall();
+        assert!(wall.init().is_ok());
+        let note = wall.create_note_with("note", &["todo".into()]).unwrap();
+        // Nothing reached the disk yet
+        assert!(note.dirty());
+
+        // A failed save keeps the note dirty until a retry succeeds
+        let path = folder.join("note").with_extension(FILE_EXTENSION);
+        assert!(note.set_path(folder.join("missing/note.post")).is_ok());
+        assert!(note.save().is_err());
+        assert!(note.dirty());
+        assert!(note.set_path(path).is_ok());
+        assert!(note.save().is_ok());
+        assert!(!note.dirty());
+
+        // Another wall reads back what was saved
+        let mut reloaded = new_wall();
+        assert!(reloaded.init().is_ok());
+        assert_eq!(reloaded.get_notes().len(), 1);
+        assert!(reloaded.get_notes().iter().all(|note| !note.dirty()));
+
+        let _ = fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn legacy_format_test() {
+        let folder = Path::new("/tmp/test_todotui_legacy");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+
+        let legacy = InternalNoteV0 {
+            title: "legacy".to_string(),
+            created: "01_01_2024_00:00_000000".to_string(),
+            todos: vec![InternalTodoV3 {
+                done: Some(true),
+                description: "desc".to_string(),
+            }],
+        };
+        fs::write(folder.join("legacy.post"), to_stdvec(&legacy).unwrap()).unwrap();
+        let version_1 = InternalNoteV1 {
+            title: "version 1".to_string(),
+            created: "02_01_2024_00:00_000000".to_string(),
+            todos: vec![],
+            hide_completed: true,
+        };
+        let mut data = [MAGIC, &[1]].concat();
+        data.extend(to_stdvec(&version_1).unwrap());
+        fs::write(folder.join("version_1.post"), data).unwrap();
+        let version_2 = InternalNoteV2 {
+            title: "version 2".to_string(),
+            created: "03_01_2024_00:00_000000".to_string(),
+            todos: vec![],
+            hide_completed: false,
+            archived: true,
+        };
+        let mut data = [MAGIC, &[2]].concat();
+        data.extend(to_stdvec(&version_2).unwrap());
+        fs::write(folder.join("version_2.post"), data).unwrap();
+
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .build()
+            .unwrap();
+        assert!(wall.init().is_ok());
+        let version_1: Note = wall.get_notes().pop().unwrap();
+        assert_eq!(version_1.title().unwrap(), "version 1");
+        assert!(version_1.hide_completed() && !version_1.archived() && !version_1.pinned());
+        wall.set_archive(true);
+        assert_eq!(wall.get_notes()[0].title().unwrap(), "version 2");
+        wall.set_archive(false);
+        let note: Note = wall.get_notes().remove(0);
+        assert_eq!(note.title().unwrap(), "legacy");
+        assert_eq!(note.todos()[0].description().unwrap(), "desc");
+        assert_eq!(note.todos()[0].created().unwrap(), None);
+        assert_eq!(note.todos()[0].completed().unwrap(), None);
+        assert!(!note.hide_completed());
+
+        // Saved again with the current format
+        assert!(note.set_hide_completed(true).is_ok());
+        assert!(note.save().is_ok());
+        assert!(wall.init().is_ok());
+        assert!(wall.get_notes()[0].hide_completed());
+
+        // New todos have timestamps, only done ones keep their completion time
+        let todo = Todo::default();
+        assert!(todo.created().unwrap().is_some());
+        assert!(todo.set_done(Some(true)).is_ok());
+        let completed = todo.completed().unwrap();
+        assert!(completed.is_some());
+        assert!(todo.set_done(Some(true)).is_ok());
+        assert_eq!(todo.completed().unwrap(), completed);
+        assert!(todo.set_done(None).is_ok());
+        assert_eq!(todo.completed().unwrap(), None);
+
+        let _ = fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn remove_duplicate_test() {
+        let mut note = Note::default();
+        let original = note.create_todo().unwrap();
+        assert!(original.set_description("milk").is_ok());
+        let first = original.duplicate().unwrap();
+        let second = original.duplicate().unwrap();
+        assert!(note.insert_todo(1, first.clone()).is_ok());
+        assert!(note.insert_todo(2, second.clone()).is_ok());
+        assert!(first == original && second == original);
+
+        // Removing the pasted copies leaves the original in place
+        assert!(note.remove_todo(&second).is_ok());
+        assert!(note.remove_todo(&first).is_ok());
+        let todos = note.todos();
+        assert_eq!(todos.len(), 1);
+        assert!(todos[0].is(&original));
+    }
+
+    #[test]
+    fn order_test() {
+        let folder = Path::new("/tmp/test_todotui_order");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let new_wall = || {
+            NotesWallBuilder::default()
+                .folder_path(folder.to_path_buf())
+                .build()
+                .unwrap()
+        };
+        let titles = |wall: &NotesWall| -> Vec<String> {
+            wall.get_notes()
+                .iter()
+                .map(|note| note.title().unwrap())
+                .collect()
+        };
+
+        let mut wall = new_wall();
+        assert!(wall.init().is_ok());
+        for title in ["a", "b", "c"] {
+            let mut note = wall.create_note();
+            assert!(
+                note.set_path(folder.join(format!("{}.post", title)))
+                    .is_ok()
+            );
+            assert!(note.set_title(title).is_ok());
+            for description in ["1", "2", "3"] {
+                let todo = note.create_todo().unwrap();
+                assert!(todo.set_description(description).is_ok());
+            }
+            assert!(note.move_todo(0, 2).is_ok());
+            assert!(note.save().is_ok());
+        }
+        assert!(wall.move_note(2, 0).is_ok());
+        assert_eq!(titles(&wall), vec!["c", "a", "b"]);
+
+        let mut wall = new_wall();
+        assert!(wall.init().is_ok());
+        assert_eq!(titles(&wall), vec!["c", "a", "b"]);
+        let descriptions: Vec<String> = wall.get_notes()[0]
+            .todos()
+            .iter()
+            .map(|todo| todo.description().unwrap())
+            .collect();
+        assert_eq!(descriptions, vec!["2", "3", "1"]);
+
+        let _ = fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn sort_test() {
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(Path::new("/tmp/test_todotui_sort").to_path_buf())
+            .build()
+            .unwrap();
+        for (title, open) in [("b", 1), ("C", 2), ("a", 1)] {
+            let mut note = wall.create_note();
+            assert!(note.set_title(title).is_ok());
+            for _ in 0..open {
+                assert!(note.create_todo().unwrap().set_done(Some(false)).is_ok());
+            }
+        }
+        let titles = |wall: &NotesWall| -> Vec<String> {
+            wall.get_notes()
+                .iter()
+                .map(|note| note.title().unwrap())
+                .collect()
+        };
+
+        assert_eq!(titles(&wall), vec!["b", "C", "a"]);
+        wall.set_sort(SortMode::Title);
+        assert_eq!(titles(&wall), vec!["a", "b", "C"]);
+        // Equal counts keep the creation order
+        wall.set_sort(SortMode::OpenTodos);
+        assert_eq!(titles(&wall), vec!["C", "b", "a"]);
+        wall.set_sort(SortMode::Created);
+        assert_eq!(titles(&wall), vec!["b", "C", "a"]);
+        assert!(wall.move_note(0, 1).is_err());
+    }
+
+    #[test]
+    fn transfer_test() {
+        let folder = Path::new("/tmp/test_todotui_transfer");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let new_wall = || {
+            NotesWallBuilder::default()
+                .folder_path(folder.to_path_buf())
+                .build()
+                .unwrap()
+        };
+        let descriptions = |note: &Note| -> Vec<String> {
+            note.todos()
+                .iter()
+                .map(|todo| todo.description().unwrap())
+                .collect()
+        };
+
+        let mut wall = new_wall();
+        assert!(wall.init().is_ok());
+        let mut source = wall.create_note();
+        assert!(source.set_path(folder.join("source.post")).is_ok());
+        for description in ["1", "2"] {
+            let todo = source.create_todo().unwrap();
+            assert!(todo.set_description(description).is_ok());
+        }
+        let target = wall.create_note();
+        assert!(target.set_path(folder.join("target.post")).is_ok());
+
+        assert!(wall.transfer_todos(&source, &[0], &target, false).is_ok());
+        assert!(wall.transfer_todos(&source, &[0], &target, true).is_ok());
+        assert_eq!(descriptions(&source), vec!["2"]);
+        assert_eq!(descriptions(&target), vec!["1", "2"]);
+        assert!(!source.dirty() && !target.dirty());
+        // The copy doesn't share its data with the original
+        assert!(target.todos()[1].set_description("changed").is_ok());
+        assert_eq!(descriptions(&source), vec!["2"]);
+
+        let mut reloaded = new_wall();
+        assert!(reloaded.init().is_ok());
+        let notes = reloaded.get_notes();
+        assert_eq!(descriptions(&notes[0]), vec!["2"]);
+        assert_eq!(descriptions(&notes[1]), vec!["1", "2"]);
+
+        // Several todos keep their order and moving keeps them
+        let moved = target.todos()[1].clone();
+        assert!(
+            wall.transfer_todos(&target, &[1, 0], &source, false)
+                .is_ok()
+        );
+        assert_eq!(descriptions(&source), vec!["2", "1", "changed"]);
+        assert!(target.todos().is_empty());
+        assert!(source.todos()[2].is(&moved));
+
+        // Nothing changes when a file can't be written
+        assert!(target.set_path(folder.join("missing/target.post")).is_ok());
+        assert!(wall.transfer_todos(&source, &[0], &target, false).is_err());
+        assert_eq!(descriptions(&source), vec!["2", "1", "changed"]);
+        assert!(!folder.join("source.tmp").exists());
+        assert!(
+            wall.transfer_todos(&source, &[0, 5], &target, false)
+                .is_err()
+        );
+        // A failed rename puts back the file already replaced
+        assert!(target.set_path(folder.join("target.post")).is_ok());
+        let saved = fs::read(folder.join("target.post")).unwrap();
+        fs::create_dir_all(folder.join("blocked.post/inside")).unwrap();
+        assert!(source.set_path(folder.join("blocked.post")).is_ok());
+        assert!(wall.transfer_todos(&source, &[0], &target, false).is_err());
+        assert_eq!(fs::read(folder.join("target.post")).unwrap(), saved);
+        assert!(!folder.join("target.bak").exists());
+        assert!(!folder.join("target.tmp").exists());
+        assert!(target.todos().is_empty());
+
+        let _ = fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn merge_split_test() {
+        let folder = Path::new("/tmp/test_todotui_merge");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let descriptions = |note: &Note| -> Vec<String> {
+            note.todos()
+                .iter()
+                .map(|todo| todo.description().unwrap())
+                .collect()
+        };
+
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .build()
+            .unwrap();
+        let target = wall
+            .create_note_with("target", &["a".into(), "b".into()])
+            .unwrap();
+        let source = wall
+            .create_note_with("source", &["b".into(), "c".into(), "c".into()])
+            .unwrap();
+        assert!(target.save().is_ok() && source.save().is_ok());
+
+        assert!(wall.merge_notes(&target, &target).is_err());
+        let kept = target.todos();
+        assert!(wall.merge_notes(&target, &source).is_ok());
+        assert_eq!(descriptions(&target), vec!["a", "b", "c"]);
+        assert!(target.todos()[0].is(&kept[0]) && target.todos()[1].is(&kept[1]));
+        assert_eq!(wall.get_notes(), vec![target.clone()]);
+
+        let split = wall.split_note(&target, &[0, 2]).unwrap();
+        assert_eq!(descriptions(&target), vec!["b"]);
+        assert_eq!(descriptions(&split), vec!["a", "c"]);
+        assert_eq!(split.title().unwrap(), "target (split)");
+        assert!(!split.dirty());
+        assert!(wall.split_note(&target, &[3]).is_err());
+        assert_eq!(wall.get_notes().len(), 2);
+
+        let mut reloaded = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .build()
+            .unwrap();
+        assert!(reloaded.init().is_ok());
+        assert_eq!(reloaded.get_notes().len(), 2);
+
+        let _ = fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn archive_test() {
+        let folder = Path::new("/tmp/test_todotui_archive");
+        let _ = fs::remove_dir_all(folder);
+        fs::create_dir_all(folder).unwrap();
+        let titles = |wall: &NotesWall| -> Vec<String> {
+            wall.get_notes()
+                .iter()
+                .map(|note| note.title().unwrap())
+                .collect()
+        };
+
+        let mut wall = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .build()
+            .unwrap();
+        for title in ["a", "b", "c", "d"] {
+            let note = wall.create_note_with(title, &["todo".into()]).unwrap();
+            assert!(
+                note.set_path(folder.join(title).with_extension(FILE_EXTENSION))
+                    .is_ok()
+            );
+            assert!(note.save().is_ok());
+        }
+        let notes = wall.get_notes();
+        assert!(notes[1].set_archived(true).is_ok());
+        assert!(notes[1].save().is_ok());
+        assert_eq!(titles(&wall), vec!["a", "c", "d"]);
+
+        // Positions are in the view, the archived note keeps its place
+        assert!(wall.move_note(0, 1).is_ok());
+        assert_eq!(titles(&wall), vec!["c", "a", "d"]);
+
+        // Pinned notes stay on top, also when sorted
+        assert!(notes[3].set_pinned(true).is_ok());
+        assert!(notes[3].save().is_ok());
+        assert_eq!(titles(&wall), vec!["d", "c", "a"]);
+        assert!(wall.move_note(1, 0).is_err());
+        wall.set_sort(SortMode::Title);
+        assert_eq!(titles(&wall), vec!["d", "a", "c"]);
+        wall.set_sort(SortMode::Manual);
+        wall.set_archive(true);
+        assert_eq!(titles(&wall), vec!["b"]);
+        assert_eq!(wall.all_notes().len(), 4);
+
+        // Only the finished notes are archived
+        assert!(notes[3].todos()[0].set_done(Some(true)).is_ok());
+        assert!(notes[3].save().is_ok());
+        let mut reloaded = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .archive_after(Some(Duration::ZERO))
+            .build()
+            .unwrap();
+        assert!(reloaded.init().is_ok());
+        assert_eq!(titles(&reloaded), vec!["c", "a"]);
+        reloaded.set_archive(true);
+        assert_eq!(titles(&reloaded), vec!["d", "b"]);
+
+        // The delay runs from the last completion, not from the last save
+        let days = |days: u64| Duration::from_secs(days * 86_400);
+        let done = reloaded
+            .all_notes()
+            .into_iter()
+            .find(|note| note.title().unwrap() == "a")
+            .unwrap();
+        assert!(done.todos()[0].set_done(Some(true)).is_ok());
+        done.todos()[0].0.write().unwrap().completed = Some(Utc::now().timestamp() - 3 * 86_400);
+        assert!(done.save().is_ok());
+        let mut reloaded = NotesWallBuilder::default()
+            .folder_path(folder.to_path_buf())
+            .archive_after(Some(days(2)))
+            .build()
+            .unwrap();
+        assert!(reloaded.init().is_ok());
+        assert_eq!(titles(&reloaded), vec!["c"]);
+
+        let _ = fs::remove_dir_all(folder);
+    }
 }
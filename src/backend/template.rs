@@ -0,0 +1,139 @@
+use std::{
+    fs::{create_dir_all, read_dir, read_to_string, write},
+    path::Path,
+};
+
+use anyhow::{Context, Error, Result};
+use serde::{Deserialize, Serialize};
+
+static TEMPLATE_FOLDER: &str = "templates";
+static TEMPLATE_EXTENSION: &str = "toml";
+static DATE_VARIABLE: &str = "{{date}}";
+
+/// Title and todos of a new note, read from `templates/<name>.toml` in the data directory.
+/// `{{date}}` is replaced with the current date
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    #[serde(skip)]
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub todos: Vec<String>,
+}
+
+impl Template {
+    /// Every template of `folder_path` sorted by name, none when the folder is missing. An invalid
+    /// template doesn't hide the others, it is skipped and its error returned alongside
+    pub fn load_all(folder_path: &Path) -> Result<(Vec<Template>, Vec<Error>)> {
+        let folder = folder_path.join(TEMPLATE_FOLDER);
+        if !folder.exists() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        let mut templates = Vec::new();
+        let mut errors = Vec::new();
+        for file in read_dir(&folder)? {
+            let path = file?.path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != TEMPLATE_EXTENSION)
+            {
+                continue;
+            }
+            let template = read_to_string(&path)
+                .map_err(Error::from)
+                .and_then(|text| Ok(toml::from_str::<Template>(&text)?))
+                .with_context(|| format!("Invalid template {}", path.display()));
+            let mut template = match template {
+                Ok(template) => template,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+            template.name = path
+                .file_stem()
+                .map_or(String::new(), |name| name.to_string_lossy().to_string());
+            templates.push(template);
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok((templates, errors))
+    }
+
+    pub fn save(&self, folder_path: &Path) -> Result<()> {
+        let folder = folder_path.join(TEMPLATE_FOLDER);
+        create_dir_all(&folder)?;
+        let name = self.name.replace(['/', '\\'], "_");
+        write(
+            // `with_extension` would cut dotted names like "v1.2 release"
+            folder.join(format!("{name}.{TEMPLATE_EXTENSION}")),
+            toml::to_string(self)?,
+        )
+        .with_context(|| format!("Cannot save the template {}", self.name))
+    }
+
+    /// Title and todos with the variables replaced
+    pub fn render(&self, date: &str) -> (String, Vec<String>) {
+        let substitute = |text: &str| text.replace(DATE_VARIABLE, date);
+        (
+            substitute(&self.title),
+            self.todos.iter().map(|todo| substitute(todo)).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::Template;
+
+    #[test]
+    fn template_test() {
+        let folder = Path::new("/tmp/test_todotui_template");
+        let _ = fs::remove_dir_all(folder);
+        assert_eq!(Template::load_all(folder).unwrap().0, vec![]);
+
+        let template = Template {
+            name: "Release checklist".to_string(),
+            title: "Release {{date}}".to_string(),
+            todos: vec!["Tag {{date}}".to_string(), "Publish".to_string()],
+        };
+        assert!(template.save(folder).is_ok());
+        fs::write(folder.join("templates/notes.txt"), "ignored").unwrap();
+
+        let templates = Template::load_all(folder).unwrap().0;
+        assert_eq!(templates, vec![template]);
+        assert_eq!(
+            templates[0].render("2024-05-01"),
+            (
+                "Release 2024-05-01".to_string(),
+                vec!["Tag 2024-05-01".to_string(), "Publish".to_string()]
+            )
+        );
+
+        // Dotted names keep their own files
+        let dotted: Vec<Template> = ["v1.2 release", "v1.3 release"]
+            .into_iter()
+            .map(|name| Template {
+                name: name.to_string(),
+                title: name.to_string(),
+                todos: vec![],
+            })
+            .collect();
+        for template in &dotted {
+            assert!(template.save(folder).is_ok());
+        }
+        let templates = Template::load_all(folder).unwrap().0;
+        assert_eq!(templates[1..], dotted[..]);
+
+        // The invalid template is reported, the others are still loaded
+        fs::write(folder.join("templates/bad.toml"), "title = 1").unwrap();
+        let (loaded, errors) = Template::load_all(folder).unwrap();
+        assert_eq!(loaded, templates);
+        assert_eq!(errors.len(), 1);
+        assert!(format!("{:#}", errors[0]).contains("bad.toml"));
+
+        let _ = fs::remove_dir_all(folder);
+    }
+}
@@ -0,0 +1,500 @@
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+use super::Candidate;
+use crate::{KeyMode, Msg};
+
+/// Where an action is available
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    /// Both lists
+    Global,
+    NoteList,
+    TodoList,
+    Editor,
+    /// Sequences parsed by `VimKeys`, on top of the other bindings
+    Vim,
+}
+
+impl Context {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Context::Global => "General",
+            Context::NoteList => "Note list",
+            Context::TodoList => "Item list",
+            Context::Editor => "Editor",
+            Context::Vim => "Vim mode",
+        }
+    }
+
+    pub fn available(&self, key_mode: KeyMode) -> bool {
+        *self != Context::Vim || key_mode == KeyMode::Vim
+    }
+}
+
+/// Entry of the action registry, the single source for key dispatching and help
+pub struct Action {
+    pub context: Context,
+    /// Key as shown to the user
+    pub label: &'static str,
+    /// Key dispatched through the registry, `None` when the component handles it by itself
+    pub key: Option<KeyEvent>,
+    pub description: &'static str,
+    pub msg: Option<Msg>,
+}
+
+const fn key(code: Key) -> Option<KeyEvent> {
+    Some(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+const fn action(
+    context: Context,
+    label: &'static str,
+    key: Option<KeyEvent>,
+    description: &'static str,
+    msg: Option<Msg>,
+) -> Action {
+    Action {
+        context,
+        label,
+        key,
+        description,
+        msg,
+    }
+}
+
+const fn ctrl(code: Key) -> Option<KeyEvent> {
+    Some(KeyEvent {
+        code,
+        modifiers: KeyModifiers::CONTROL,
+    })
+}
+
+const fn alt(code: Key) -> Option<KeyEvent> {
+    Some(KeyEvent {
+        code,
+        modifiers: KeyModifiers::ALT,
+    })
+}
+
+/// Documented only, handled by the component
+const fn info(context: Context, label: &'static str, description: &'static str) -> Action {
+    action(context, label, None, description, None)
+}
+
+pub static ACTIONS: &[Action] = &[
+    action(Context::Global, "ESC", None, "Quit", Some(Msg::AppClose)),
+    action(
+        Context::Global,
+        "?",
+        key(Key::Char('?')),
+        "Show this help",
+        Some(Msg::ShowHelp),
+    ),
+    action(
+        Context::Global,
+        "r",
+        key(Key::Char('r')),
+        "Retry the failed saves",
+        Some(Msg::RetrySave),
+    ),
+    action(
+        Context::Global,
+        "/",
+        key(Key::Char('/')),
+        "Search the notes and the items",
+        Some(Msg::OpenSearch),
+    ),
+    action(
+        Context::Global,
+        "n",
+        key(Key::Char('n')),
+        "Go to the next match",
+        Some(Msg::SearchNext),
+    ),
+    action(
+        Context::Global,
+        "N",
+        key(Key::Char('N')),
+        "Go to the previous match",
+        Some(Msg::SearchPrevious),
+    ),
+    action(
+        Context::Global,
+        "C-p",
+        ctrl(Key::Char('p')),
+        "Go to a note by its title",
+        Some(Msg::OpenSwitcher),
+    ),
+    action(
+        Context::Global,
+        ":",
+        key(Key::Char(':')),
+        "Open the command palette",
+        Some(Msg::OpenPalette),
+    ),
+    action(
+        Context::Global,
+        "i",
+        key(Key::Char('i')),
+        "Show the statistics",
+        Some(Msg::ShowStats),
+    ),
+    action(
+        Context::Global,
+        "L",
+        key(Key::Char('L')),
+        "Show the history of the note",
+        Some(Msg::ShowHistory),
+    ),
+    action(
+        Context::Global,
+        "V",
+        key(Key::Char('V')),
+        "Browse the versions of the note (git)",
+        Some(Msg::ShowVersions(0)),
+    ),
+    action(
+        Context::Global,
+        "t",
+        key(Key::Char('t')),
+        "Change the theme...",
+        Some(Msg::PickTheme),
+    ),
+    info(Context::Global, "Up/Down", "Select the previous/next row"),
+    info(Context::Global, "PgUp/PgDn", "Scroll a page up/down"),
+    info(Context::Global, "Home/End", "Select the first/last row"),
+    action(
+        Context::NoteList,
+        "TAB",
+        key(Key::Tab),
+        "Focus the item list",
+        Some(Msg::NoteListBlur),
+    ),
+    action(
+        Context::NoteList,
+        "a",
+        key(Key::Char('a')),
+        "Add a note, blank or from a template",
+        Some(Msg::AddNote),
+    ),
+    action(
+        Context::NoteList,
+        "D",
+        key(Key::Char('D')),
+        "Duplicate the note, every item open again",
+        Some(Msg::DuplicateNote),
+    ),
+    action(
+        Context::NoteList,
+        "T",
+        key(Key::Char('T')),
+        "Save the note as a template",
+        Some(Msg::SaveTemplate),
+    ),
+    action(
+        Context::NoteList,
+        "M",
+        key(Key::Char('M')),
+        "Merge another note into this one",
+        Some(Msg::PickNoteForMerge),
+    ),
+    action(
+        Context::NoteList,
+        "P",
+        key(Key::Char('P')),
+        "Pin the note on top, or unpin it",
+        Some(Msg::TogglePinned),
+    ),
+    action(
+        Context::NoteList,
+        "A",
+        key(Key::Char('A')),
+        "Archive the note, or restore it from the archive",
+        Some(Msg::ToggleArchived),
+    ),
+    action(
+        Context::NoteList,
+        "z",
+        key(Key::Char('z')),
+        "Show the archive or the notes",
+        Some(Msg::ToggleArchiveView),
+    ),
+    action(
+        Context::NoteList,
+        "e",
+        key(Key::Char('e')),
+        "Rename the note",
+        Some(Msg::EditNote),
+    ),
+    action(
+        Context::NoteList,
+        "d",
+        key(Key::Char('d')),
+        "Delete the note",
+        Some(Msg::RemoveNote),
+    ),
+    action(
+        Context::NoteList,
+        "s",
+        key(Key::Char('s')),
+        "Cycle the order: manual, created, modified, title, open items",
+        Some(Msg::CycleSortMode),
+    ),
+    action(
+        Context::NoteList,
+        "o",
+        key(Key::Char('o')),
+        "Sort the notes by...",
+        Some(Msg::PickSortMode),
+    ),
+    action(
+        Context::NoteList,
+        "Alt-Up",
+        alt(Key::Up),
+        "Move the note up",
+        Some(Msg::MoveNoteUp),
+    ),
+    action(
+        Context::NoteList,
+        "Alt-Down",
+        alt(Key::Down),
+        "Move the note down",
+        Some(Msg::MoveNoteDown),
+    ),
+    action(
+        Context::TodoList,
+        "TAB",
+        key(Key::Tab),
+        "Focus the note list",
+        Some(Msg::TodoListBlur),
+    ),
+    action(
+        Context::TodoList,
+        "a",
+        key(Key::Char('a')),
+        "Add an item",
+        Some(Msg::AddTodo),
+    ),
+    action(
+        Context::TodoList,
+        "e",
+        key(Key::Char('e')),
+        "Edit the item",
+        Some(Msg::EditTodo),
+    ),
+    action(
+        Context::TodoList,
+        "d",
+        key(Key::Char('d')),
+        "Delete the marked items, or the item",
+        Some(Msg::RemoveTodo),
+    ),
+    action(
+        Context::TodoList,
+        "m",
+        key(Key::Char('m')),
+        "Move the marked items, or the item, to another note",
+        Some(Msg::PickNoteForMove),
+    ),
+    action(
+        Context::TodoList,
+        "c",
+        key(Key::Char('c')),
+        "Copy the marked items, or the item, to another note",
+        Some(Msg::PickNoteForCopy),
+    ),
+    action(
+        Context::TodoList,
+        "v",
+        key(Key::Char('v')),
+        "Start or clear a range of items",
+        Some(Msg::ToggleRangeAnchor),
+    ),
+    action(
+        Context::TodoList,
+        "x",
+        key(Key::Char('x')),
+        "Mark or unmark the range or the item for the bulk actions",
+        Some(Msg::ToggleSelection),
+    ),
+    action(
+        Context::TodoList,
+        "X",
+        key(Key::Char('X')),
+        "Unmark every item",
+        Some(Msg::ClearSelection),
+    ),
+    action(
+        Context::TodoList,
+        "S",
+        key(Key::Char('S')),
+        "Split the range or the item into a new note",
+        Some(Msg::SplitNote),
+    ),
+    action(
+        Context::TodoList,
+        "Alt-Up",
+        alt(Key::Up),
+        "Move the item up",
+        Some(Msg::MoveTodoUp),
+    ),
+    action(
+        Context::TodoList,
+        "Alt-Down",
+        alt(Key::Down),
+        "Move the item down",
+        Some(Msg::MoveTodoDown),
+    ),
+    action(
+        Context::TodoList,
+        "SPC",
+        key(Key::Char(' ')),
+        "Cycle the item status, the marked items follow",
+        Some(Msg::SwitchTodoStatus),
+    ),
+    action(
+        Context::TodoList,
+        "f",
+        key(Key::Char('f')),
+        "Cycle the status filter: all, open, done, unknown",
+        Some(Msg::CycleTodoFilter),
+    ),
+    action(
+        Context::TodoList,
+        "F",
+        key(Key::Char('F')),
+        "Filter the items by status...",
+        Some(Msg::PickTodoFilter),
+    ),
+    action(
+        Context::TodoList,
+        "H",
+        key(Key::Char('H')),
+        "Hide or show the done items of the note",
+        Some(Msg::ToggleHideCompleted),
+    ),
+    info(Context::Editor, "ENTER", "Confirm"),
+    info(Context::Editor, "ESC", "Cancel"),
+    info(Context::Editor, "Left/Right", "Move the cursor"),
+    info(Context::Editor, "Home/End", "Go to the beginning/end"),
+    info(Context::Editor, "BS/DEL", "Delete before/under the cursor"),
+    info(Context::Vim, "j/k", "Move down/up, a count repeats"),
+    info(Context::Vim, "gg/G", "Go to the first/last row"),
+    info(Context::Vim, "{n}G", "Go to row n"),
+    info(Context::Vim, "h/l", "Focus the note/item list"),
+    info(Context::Vim, "dd", "Delete the note, cut the item"),
+    info(Context::Vim, "yy/p", "Copy the item, paste it below"),
+    info(Context::Vim, ".", "Repeat the last change"),
+];
+
+/// Message bound to `ev` in `context`, global actions included
+pub fn dispatch(context: Context, ev: &KeyEvent) -> Option<Msg> {
+    ACTIONS
+        .iter()
+        .filter(|action| action.context == context || action.context == Context::Global)
+        .find(|action| action.key.is_some_and(|key| matches(&key, ev)))
+        .and_then(|action| action.msg.clone())
+}
+
+/// Actions of the palette, the focused context first. Those needing an argument prompt for it
+pub fn commands(focus: Context) -> Vec<Candidate> {
+    let mut contexts = vec![focus];
+    contexts.extend(
+        [Context::Global, Context::NoteList, Context::TodoList]
+            .into_iter()
+            .filter(|context| *context != focus),
+    );
+    contexts
+        .into_iter()
+        .flat_map(|context| {
+            ACTIONS
+                .iter()
+                .filter(move |action| action.context == context)
+        })
+        .filter(|action| {
+            action
+                .msg
+                .as_ref()
+                .is_some_and(|msg| *msg != Msg::OpenPalette)
+        })
+        .map(|action| {
+            Candidate::new(action.description, action.msg.clone().unwrap())
+                .with_context(action.context.title())
+                .with_hint(action.label)
+        })
+        .collect()
+}
+
+/// Shift is already part of the character, only Control and Alt make a difference
+fn matches(key: &KeyEvent, ev: &KeyEvent) -> bool {
+    let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT;
+    key.code == ev.code && (key.modifiers & relevant) == (ev.modifiers & relevant)
+}
+
+#[cfg(test)]
+mod tests {
+    use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+    use super::{Context, commands, dispatch};
+    use crate::Msg;
+
+    #[test]
+    fn dispatch_test() {
+        let ev = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        assert_eq!(
+            dispatch(Context::NoteList, &ev(Key::Char('a'), KeyModifiers::NONE)),
+            Some(Msg::AddNote)
+        );
+        assert_eq!(
+            dispatch(Context::TodoList, &ev(Key::Char('a'), KeyModifiers::NONE)),
+            Some(Msg::AddTodo)
+        );
+        // Global actions are available everywhere, shift doesn't matter
+        assert_eq!(
+            dispatch(Context::TodoList, &ev(Key::Char('?'), KeyModifiers::SHIFT)),
+            Some(Msg::ShowHelp)
+        );
+        assert_eq!(
+            dispatch(
+                Context::NoteList,
+                &ev(Key::Char('a'), KeyModifiers::CONTROL)
+            ),
+            None
+        );
+        // Documented only
+        assert_eq!(
+            dispatch(Context::NoteList, &ev(Key::Down, KeyModifiers::NONE)),
+            None
+        );
+    }
+
+    #[test]
+    fn commands_test() {
+        let commands = commands(Context::TodoList);
+        assert_eq!(commands[0].target, Msg::TodoListBlur);
+        assert!(
+            commands
+                .iter()
+                .any(|command| command.target == Msg::AddNote)
+        );
+        // No documentation only entries and no palette inside the palette
+        assert!(
+            commands
+                .iter()
+                .all(|command| command.target != Msg::OpenPalette)
+        );
+        assert!(
+            !commands
+                .iter()
+                .any(|command| command.text.contains("Up/Down"))
+        );
+        // The actions prompting for their value are listed too
+        for target in [Msg::PickSortMode, Msg::PickTodoFilter, Msg::PickTheme] {
+            assert!(commands.iter().any(|command| command.target == target));
+        }
+    }
+}
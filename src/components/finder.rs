@@ -0,0 +1,406 @@
+use std::sync::LazyLock;
+
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use tui_realm_stdlib::{Input, List};
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+    props::{
+        Alignment, Borders, Color, InputType, PropPayload, PropValue, Table, TableBuilder, TextSpan,
+    },
+    ratatui::layout::{self, Constraint, Layout, Rect},
+};
+
+use super::{maybe_scroll_list, mouse::Clickable};
+use crate::{AppEvent, Msg, config::Theme};
+
+/// Something the finder can pick
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Shown before the text, dimmed and not matched
+    pub context: Option<String>,
+    pub text: String,
+    /// Shown after the text, dimmed and not matched
+    pub hint: Option<String>,
+    /// Sent when the candidate is picked
+    pub target: Msg,
+}
+
+impl Candidate {
+    pub fn new(text: impl Into<String>, target: Msg) -> Self {
+        Self {
+            context: None,
+            text: text.into(),
+            hint: None,
+            target,
+        }
+    }
+
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// How the query is matched against the candidates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Matching {
+    /// Case insensitive substring, the candidates keep their order
+    Substring,
+    /// Skim like, smart case, the best ranked candidates first
+    Fuzzy,
+}
+
+static FUZZY_MATCHER: LazyLock<SkimMatcherV2> =
+    LazyLock::new(|| SkimMatcherV2::default().smart_case());
+
+impl Matching {
+    /// Score, the higher the better, and the matched char indices
+    pub fn find(&self, query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+        match self {
+            Matching::Substring => substring_indices(query, text).map(|indices| (0, indices)),
+            Matching::Fuzzy => FUZZY_MATCHER.fuzzy_indices(text, query),
+        }
+    }
+}
+
+/// Char indices of the first case insensitive occurrence of `query` in `text`
+pub fn substring_indices(query: &str, text: &str) -> Option<Vec<usize>> {
+    let lower = |text: &str| -> Vec<char> {
+        text.chars()
+            .map(|ch| ch.to_lowercase().next().unwrap_or(ch))
+            .collect()
+    };
+    let (query, text) = (lower(query), lower(text));
+    if query.is_empty() || query.len() > text.len() {
+        return None;
+    }
+    (0..=text.len() - query.len())
+        .find(|start| text[*start..*start + query.len()] == query[..])
+        .map(|start| (start..start + query.len()).collect())
+}
+
+/// Split `text` in spans, the chars at `indices` are highlighted
+pub fn highlight_spans(text: &str, indices: &[usize], color: Color) -> Vec<TextSpan> {
+    let mut spans: Vec<(bool, String)> = Vec::new();
+    for (index, ch) in text.chars().enumerate() {
+        let matched = indices.contains(&index);
+        match spans.last_mut() {
+            Some((last, content)) if *last == matched => content.push(ch),
+            _ => spans.push((matched, ch.to_string())),
+        }
+    }
+    spans
+        .into_iter()
+        .map(|(matched, content)| match matched {
+            true => TextSpan::from(content).fg(color).bold().underlined(),
+            false => TextSpan::from(content),
+        })
+        .collect()
+}
+
+/// Popup with a query line on top of the matching candidates, live filtered while typing
+pub struct Finder {
+    input: Input,
+    list: Clickable<List>,
+    candidates: Vec<Candidate>,
+    matching: Matching,
+    /// Indices of the matching candidates, best first, with the matched chars
+    results: Vec<(usize, Vec<usize>)>,
+    /// Sent on every query change
+    on_change: Option<fn(String) -> Msg>,
+    theme: Theme,
+    area: Rect,
+}
+
+impl Finder {
+    pub fn new(
+        title: &str,
+        query: &str,
+        candidates: Vec<Candidate>,
+        matching: Matching,
+        theme: &Theme,
+    ) -> Self {
+        let borders = || {
+            Borders::default()
+                .modifiers(theme.popup_border_type)
+                .color(theme.popup_border)
+        };
+        let mut finder = Self {
+            input: Input::default()
+                .borders(borders())
+                .foreground(theme.popup_text)
+                .input_type(InputType::Text)
+                .title(title, Alignment::Left)
+                .value(query),
+            list: Clickable::new(
+                List::default()
+                    .borders(borders())
+                    .highlighted_color(theme.highlight)
+                    .highlighted_str(&theme.highlight_str)
+                    .scroll(true)
+                    .rewind(true),
+            ),
+            candidates,
+            matching,
+            results: Vec::new(),
+            on_change: None,
+            theme: theme.clone(),
+            area: Rect::default(),
+        };
+        finder.refresh();
+        finder
+    }
+
+    pub fn on_change(mut self, on_change: fn(String) -> Msg) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+
+    fn query(&self) -> String {
+        match self.input.state() {
+            State::One(value) => value.unwrap_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Match the candidates against the query, an empty query matches everything
+    fn refresh(&mut self) {
+        let query = self.query();
+        self.results = match query.is_empty() {
+            true => (0..self.candidates.len())
+                .map(|index| (index, vec![]))
+                .collect(),
+            false => {
+                let mut scored: Vec<(i64, usize, Vec<usize>)> = self
+                    .candidates
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, candidate)| {
+                        self.matching
+                            .find(&query, &candidate.text)
+                            .map(|(score, indices)| (score, index, indices))
+                    })
+                    .collect();
+                // Stable, equal scores keep the candidates order
+                scored.sort_by(|a, b| b.0.cmp(&a.0));
+                scored
+                    .into_iter()
+                    .map(|(_, index, indices)| (index, indices))
+                    .collect()
+            }
+        };
+        self.list.attr(
+            Attribute::Content,
+            AttrValue::Table(self.build_table_results()),
+        );
+        self.list.attr(
+            Attribute::Value,
+            AttrValue::Payload(PropPayload::One(PropValue::Usize(0))),
+        );
+    }
+
+    fn build_table_results(&self) -> Table {
+        if self.results.is_empty() {
+            return vec![];
+        }
+        let mut table = TableBuilder::default();
+        for (row, (index, indices)) in self.results.iter().enumerate() {
+            let candidate = &self.candidates[*index];
+            if row > 0 {
+                table.add_row();
+            }
+            if let Some(context) = &candidate.context {
+                table.add_col(TextSpan::from(format!("{}: ", context)).fg(self.theme.index));
+            }
+            for span in highlight_spans(&candidate.text, indices, self.theme.highlight) {
+                table.add_col(span);
+            }
+            if let Some(hint) = &candidate.hint {
+                table.add_col(TextSpan::from(format!("  {}", hint)).fg(self.theme.index));
+            }
+        }
+        table.build()
+    }
+
+    fn selected(&self) -> Option<Msg> {
+        let row = match self.list.state() {
+            State::One(value) => value.unwrap_usize(),
+            _ => 0,
+        };
+        self.results
+            .get(row)
+            .map(|(index, _)| self.candidates[*index].target.clone())
+    }
+
+    fn close(&self, target: Option<Msg>) -> Option<Msg> {
+        Some(Msg::CloseFinder(target.map(Box::new)))
+    }
+
+    fn edited(&mut self) -> Option<Msg> {
+        self.refresh();
+        match self.on_change {
+            Some(on_change) => Some(on_change(self.query())),
+            None => Some(Msg::None),
+        }
+    }
+
+    fn on_mouse(&mut self, ev: MouseEvent) -> Option<Msg> {
+        match ev.kind {
+            MouseEventKind::Down(MouseButton::Left) => match self.list.row_at(&ev) {
+                Some(row) => {
+                    self.list.attr(
+                        Attribute::Value,
+                        AttrValue::Payload(PropPayload::One(PropValue::Usize(row))),
+                    );
+                    self.close(self.selected())
+                }
+                None if self.area.contains(layout::Position::new(ev.column, ev.row)) => {
+                    Some(Msg::None)
+                }
+                None => self.close(None),
+            },
+            MouseEventKind::ScrollDown => {
+                self.list.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            MouseEventKind::ScrollUp => {
+                self.list.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl MockComponent for Finder {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(area);
+        self.input.view(frame, chunks[0]);
+        self.list.view(frame, chunks[1]);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.input.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if attr == Attribute::Focus {
+            self.list.attr(attr, value.clone());
+        }
+        self.input.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.input.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.input.perform(cmd)
+    }
+}
+
+impl Component<Msg, AppEvent> for Finder {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => self.close(None),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => self.close(self.selected()),
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.input.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.input.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.input.perform(Cmd::Delete);
+                self.edited()
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.input.perform(Cmd::Cancel);
+                self.edited()
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            }) => {
+                self.input.perform(Cmd::Type(ch));
+                self.edited()
+            }
+            // Like most fuzzy finders
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch @ ('n' | 'p')),
+                modifiers: KeyModifiers::CONTROL,
+            }) => {
+                self.list.perform(Cmd::Move(match ch {
+                    'n' => Direction::Down,
+                    _ => Direction::Up,
+                }));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home | Key::End,
+                ..
+            }) => {
+                let position = match ev {
+                    Event::Keyboard(KeyEvent {
+                        code: Key::Home, ..
+                    }) => Position::Begin,
+                    _ => Position::End,
+                };
+                self.input.perform(Cmd::GoTo(position));
+                Some(Msg::None)
+            }
+            Event::Keyboard(_) => {
+                maybe_scroll_list(&mut self.list, ev);
+                Some(Msg::None)
+            }
+            Event::Mouse(ev) => self.on_mouse(ev),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matching, substring_indices};
+
+    #[test]
+    fn substring_test() {
+        assert_eq!(substring_indices("MIL", "Buy milk"), Some(vec![4, 5, 6]));
+        assert_eq!(substring_indices("milk", "Buy mil"), None);
+        assert_eq!(substring_indices("", "Buy milk"), None);
+        // Indices are chars, not bytes
+        assert_eq!(substring_indices("è", "caffè"), Some(vec![4]));
+        assert_eq!(Matching::Substring.find("y", "Buy"), Some((0, vec![2])));
+    }
+
+    #[test]
+    fn fuzzy_test() {
+        let (_, indices) = Matching::Fuzzy.find("gcr", "Groceries").unwrap();
+        assert_eq!(indices, vec![0, 3, 5]);
+        assert_eq!(Matching::Fuzzy.find("xyz", "Groceries"), None);
+        // Consecutive matches rank higher
+        let score = |text| Matching::Fuzzy.find("work", text).unwrap().0;
+        assert!(score("Work notes") > score("Wonderful rocks"));
+    }
+}
@@ -0,0 +1,70 @@
+use tuirealm::{
+    Component, Event, MockComponent,
+    props::{Table, TableBuilder, TextSpan},
+};
+
+use super::{
+    PopupList,
+    actions::{ACTIONS, Context},
+    pad_to_width,
+};
+use crate::{AppEvent, KeyMode, Msg, config::Theme};
+
+static LABEL_WIDTH: usize = 12;
+
+/// Every action of the registry grouped by context, the focused one first
+#[derive(MockComponent)]
+pub struct Help {
+    component: PopupList,
+}
+
+impl Help {
+    pub fn new(focus: Context, key_mode: KeyMode, theme: &Theme) -> Self {
+        Self {
+            component: PopupList::new(PopupList::list(
+                "Help (? or ESC to close)",
+                Self::build_table_help(focus, key_mode, theme),
+                theme,
+            )),
+        }
+    }
+
+    fn build_table_help(focus: Context, key_mode: KeyMode, theme: &Theme) -> Table {
+        let mut contexts = vec![focus];
+        contexts.extend(
+            [
+                Context::Global,
+                Context::NoteList,
+                Context::TodoList,
+                Context::Editor,
+                Context::Vim,
+            ]
+            .into_iter()
+            .filter(|context| *context != focus && context.available(key_mode)),
+        );
+
+        let mut table = TableBuilder::default();
+        for (index, context) in contexts.into_iter().enumerate() {
+            if index > 0 {
+                table.add_row().add_col(TextSpan::from("")).add_row();
+            }
+            table.add_col(TextSpan::from(context.title()).fg(theme.highlight).bold());
+            for action in ACTIONS.iter().filter(|action| action.context == context) {
+                table
+                    .add_row()
+                    .add_col(
+                        TextSpan::from(format!(" {}", pad_to_width(action.label, LABEL_WIDTH)))
+                            .bold(),
+                    )
+                    .add_col(TextSpan::from(action.description));
+            }
+        }
+        table.build()
+    }
+}
+
+impl Component<Msg, AppEvent> for Help {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        self.component.on_event(ev, '?', Msg::CloseHelp)
+    }
+}
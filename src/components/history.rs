@@ -0,0 +1,45 @@
+use tuirealm::{
+    Component, Event, MockComponent,
+    props::{Table, TableBuilder, TextSpan},
+};
+
+use super::PopupList;
+use crate::{AppEvent, Msg, backend::Entry, config::Theme};
+
+/// Activity log of a note, the latest entry first
+#[derive(MockComponent)]
+pub struct History {
+    component: PopupList,
+}
+
+impl History {
+    pub fn new(title: &str, entries: &[Entry], theme: &Theme) -> Self {
+        Self {
+            component: PopupList::new(PopupList::list(
+                format!("History of \"{}\" (L or ESC to close)", title),
+                Self::build_table_history(entries, theme),
+                theme,
+            )),
+        }
+    }
+
+    fn build_table_history(entries: &[Entry], theme: &Theme) -> Table {
+        if entries.is_empty() {
+            return vec![vec![TextSpan::from("Nothing recorded yet")]];
+        }
+        let mut table = TableBuilder::default();
+        for entry in entries.iter().rev() {
+            table
+                .add_col(TextSpan::from(format!("{}  ", entry.time)).fg(theme.index))
+                .add_col(TextSpan::from(&entry.text))
+                .add_row();
+        }
+        table.build()
+    }
+}
+
+impl Component<Msg, AppEvent> for History {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        self.component.on_event(ev, 'L', Msg::CloseHistory)
+    }
+}
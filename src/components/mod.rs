@@ -1,4 +1,6 @@
-use tui_realm_stdlib::{Input, List, Phantom};
+use std::ops::RangeInclusive;
+
+use tui_realm_stdlib::{Input, List, Paragraph, Phantom};
 use tuirealm::{
     AttrValue, Attribute, Component, Event, MockComponent,
     command::{
@@ -6,17 +8,42 @@ use tuirealm::{
         CmdResult::{self, Changed},
         Direction, Position,
     },
-    event::{Key, KeyEvent, KeyModifiers},
+    event::{Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     props::{
-        Alignment, BorderType, Borders, Color, InputType, Style, Table, TableBuilder, TextSpan,
+        Alignment, Borders, InputType, PropPayload, PropValue, Style, Table, TableBuilder, TextSpan,
     },
 };
 
 use crate::{
-    AppEvent,
+    AppEvent, KeyMode,
     Msg::{self, NoteSelected},
     backend::{Note, Todo},
+    config::Theme,
 };
+use mouse::{ClickTracker, Clickable, Gesture};
+use unicode_width::UnicodeWidthStr;
+use vim::{VimAction, VimKeys, vim_motion};
+
+mod actions;
+mod finder;
+mod help;
+mod history;
+mod mouse;
+mod progress;
+mod stats;
+mod status_bar;
+mod versions;
+mod vim;
+
+pub use actions::{Context, commands};
+pub use finder::{Candidate, Finder, Matching, substring_indices};
+pub use help::Help;
+pub use history::History;
+pub use mouse::MouseRouter;
+pub use progress::{Progress, ProgressGauge};
+pub use stats::{StatsData, StatsView};
+pub use status_bar::{Status, StatusBar};
+pub use versions::Versions;
 
 #[derive(MockComponent, Default)]
 pub struct PhantomListener {
@@ -27,7 +54,10 @@ impl Component<Msg, AppEvent> for PhantomListener {
     fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
         let _ = match ev {
             Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => return Some(Msg::AppClose),
-            Event::User(AppEvent::ErrorInitialized) => return Some(Msg::AppClose),
+            Event::User(AppEvent::ErrorInitialized(err)) => {
+                return Some(Msg::Error(format!("Cannot load the notes: {}", err)));
+            }
+            Event::Tick => return Some(Msg::Tick),
             _ => CmdResult::None,
         };
         Some(Msg::None)
@@ -35,62 +65,102 @@ impl Component<Msg, AppEvent> for PhantomListener {
 }
 #[derive(MockComponent)]
 pub struct NoteList {
-    component: List,
+    component: Clickable<List>,
+    vim: Option<VimKeys>,
+    clicks: ClickTracker,
+    theme: Theme,
 }
 
-impl Default for NoteList {
-    fn default() -> Self {
+impl NoteList {
+    pub fn new(key_mode: KeyMode, theme: &Theme) -> Self {
         Self {
-            component: List::default()
-                .title("Note List", Alignment::Left)
-                .highlighted_color(Color::LightYellow)
-                .highlighted_str("👉")
-                .scroll(true)
-                .rewind(true)
-                .borders(
-                    Borders::default()
-                        .modifiers(BorderType::Double)
-                        .color(Color::Yellow),
-                ),
+            vim: vim_keys(key_mode),
+            clicks: ClickTracker::default(),
+            component: Clickable::new(
+                List::default()
+                    .title("Note List", Alignment::Left)
+                    .highlighted_color(theme.highlight)
+                    .highlighted_str(&theme.highlight_str)
+                    .scroll(true)
+                    .rewind(true)
+                    .borders(
+                        Borders::default()
+                            .modifiers(theme.border_type)
+                            .color(theme.border),
+                    ),
+            ),
+            theme: theme.clone(),
         }
     }
 }
 
 impl Component<Msg, AppEvent> for NoteList {
     fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        if let Some(action) = self.vim_action(&ev) {
+            return self.on_vim_action(action);
+        }
         match ev {
-            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => Some(Msg::NoteListBlur),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('e'),
-                ..
-            }) => Some(Msg::EditNote),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                ..
-            }) => Some(Msg::AddNote),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('d'),
-                ..
-            }) => Some(Msg::RemoveNote),
-            Event::Keyboard(KeyEvent { code: _, .. }) => self.maybe_scroll_note_list(ev),
+            Event::Keyboard(key) => match actions::dispatch(Context::NoteList, &key) {
+                Some(msg) => Some(msg),
+                None => self.maybe_scroll_note_list(ev),
+            },
+            Event::User(AppEvent::Mouse(ev)) => self.on_mouse(ev),
             Event::User(AppEvent::NoteLoaded(data)) => {
                 if data.is_empty() {
                     return Some(Msg::None);
                 }
                 self.component.attr(
                     Attribute::Content,
-                    AttrValue::Table(Self::build_table_note(data)),
+                    AttrValue::Table(Self::build_table_note(data, None, &self.theme)),
                 );
                 Some(NoteSelected(
                     self.component.state().unwrap_one().unwrap_usize(),
                 ))
             }
-            _ => Some(Msg::None),
+            _ => None,
         }
     }
 }
 
 impl NoteList {
+    fn vim_action(&mut self, ev: &Event<AppEvent>) -> Option<VimAction> {
+        match (self.vim.as_mut(), ev) {
+            (Some(vim), Event::Keyboard(key)) => match vim.feed(*key) {
+                VimAction::Unhandled => None,
+                action => Some(action),
+            },
+            _ => None,
+        }
+    }
+
+    fn on_vim_action(&mut self, action: VimAction) -> Option<Msg> {
+        match action {
+            VimAction::FocusRight => Some(Msg::NoteListBlur),
+            VimAction::Cut => Some(Msg::RemoveNote),
+            VimAction::Repeat => Some(Msg::RepeatLastAction),
+            _ => match vim_motion(&mut self.component, action) {
+                Changed(state) => Some(NoteSelected(state.unwrap_one().unwrap_usize())),
+                _ => None,
+            },
+        }
+    }
+
+    fn on_mouse(&mut self, ev: MouseEvent) -> Option<Msg> {
+        let current = self.component.state().unwrap_one().unwrap_usize();
+        match self.clicks.gesture(&self.component, &ev)? {
+            Gesture::Click(index) => {
+                select_row(&mut self.component, index);
+                Some(Msg::NoteClicked(index))
+            }
+            Gesture::DoubleClick(_) => Some(Msg::EditNote),
+            Gesture::Focus => Some(Msg::NoteClicked(current)),
+            gesture => match scroll_row(&mut self.component, gesture) {
+                Changed(state) => Some(NoteSelected(state.unwrap_one().unwrap_usize())),
+                _ => None,
+            },
+        }
+    }
+
     fn maybe_scroll_note_list(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
         if let Changed(state) = maybe_scroll_list(&mut self.component, ev) {
             return Some(NoteSelected(state.unwrap_one().unwrap_usize()));
@@ -98,19 +168,41 @@ impl NoteList {
         None
     }
 
-    pub fn build_table_note(notes: Vec<Note>) -> Table {
+    /// Matches of `query` are highlighted
+    pub fn build_table_note(notes: Vec<Note>, query: Option<&str>, theme: &Theme) -> Table {
         if notes.is_empty() {
             return vec![];
         }
         let mut table = TableBuilder::default();
+        let pin_width = theme.symbols.pin.width();
 
         notes.iter().enumerate().for_each(|(index, note)| {
             let index_str = format!("{:03}", index + 1);
 
+            // Unsaved notes are marked with a star, like in most editors
+            let dirty = match note.dirty() {
+                true => TextSpan::from("*").fg(theme.warning).bold(),
+                false => TextSpan::from(" "),
+            };
+            let pin = match note.pinned() {
+                true => TextSpan::from(format!("{} ", theme.symbols.pin)).fg(theme.highlight),
+                false => TextSpan::from(" ".repeat(pin_width + 1)),
+            };
+            let progress = Progress::of(note);
+            let gauge = TextSpan::from(format!("{} ", progress.compact(theme))).fg(match progress
+                .complete()
+            {
+                true => theme.highlight,
+                false => theme.index,
+            });
             let row = table
-                .add_col(TextSpan::from(index_str).fg(Color::Cyan).italic())
-                .add_col(TextSpan::from(" "))
-                .add_col(TextSpan::from(note.title().unwrap()));
+                .add_col(TextSpan::from(index_str).fg(theme.index).italic())
+                .add_col(dirty)
+                .add_col(pin)
+                .add_col(gauge);
+            for span in matched_spans(&note.title().unwrap(), query, theme) {
+                row.add_col(span);
+            }
 
             if index < notes.len() - 1 {
                 row.add_row();
@@ -122,104 +214,104 @@ impl NoteList {
 }
 
 #[derive(MockComponent)]
-pub struct ShortcutsLegend {
-    component: List,
+pub struct TodoList {
+    component: Clickable<List>,
+    vim: Option<VimKeys>,
+    clicks: ClickTracker,
+    theme: Theme,
 }
 
-impl Default for ShortcutsLegend {
-    fn default() -> Self {
+impl TodoList {
+    pub fn new(key_mode: KeyMode, theme: &Theme) -> Self {
         Self {
-            component: List::default()
-                .title("Key Bindings", Alignment::Left)
-                .scroll(false)
-                .borders(Borders::default().modifiers(BorderType::Double))
-                .rows(
-                    TableBuilder::default()
-                        .add_col(TextSpan::from(" ESC").bold())
-                        .add_col(TextSpan::from("  "))
-                        .add_col(TextSpan::from("Quit the application"))
-                        .add_col(TextSpan::from("           "))
-                        .add_col(TextSpan::from(" A").bold())
-                        .add_col(TextSpan::from("  "))
-                        .add_col(TextSpan::from("Add note/item"))
-                        .add_row()
-                        .add_col(TextSpan::from(" TAB").bold())
-                        .add_col(TextSpan::from("  "))
-                        .add_col(TextSpan::from("Switch focus"))
-                        .add_col(TextSpan::from("                   "))
-                        .add_col(TextSpan::from(" E").bold())
-                        .add_col(TextSpan::from("  "))
-                        .add_col(TextSpan::from("Edit note/item"))
-                        .add_row()
-                        .add_col(TextSpan::from(" SPC").bold())
-                        .add_col(TextSpan::from("  "))
-                        .add_col(TextSpan::from("Cycle between item status"))
-                        .add_col(TextSpan::from("      "))
-                        .add_col(TextSpan::from(" D").bold())
-                        .add_col(TextSpan::from("  "))
-                        .add_col(TextSpan::from("Delete note/item"))
-                        .build(),
-                ),
+            vim: vim_keys(key_mode),
+            clicks: ClickTracker::default(),
+            component: Clickable::new(
+                List::default()
+                    .title("Item List", Alignment::Left)
+                    .highlighted_color(theme.highlight)
+                    .highlighted_str(&theme.highlight_str)
+                    .scroll(true)
+                    .rewind(true)
+                    .borders(
+                        Borders::default()
+                            .modifiers(theme.border_type)
+                            .color(theme.border),
+                    ),
+            ),
+            theme: theme.clone(),
         }
     }
 }
 
-impl Component<Msg, AppEvent> for ShortcutsLegend {
-    fn on(&mut self, _ev: Event<AppEvent>) -> Option<Msg> {
-        Some(Msg::None)
+impl Component<Msg, AppEvent> for TodoList {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        if let Some(action) = self.vim_action(&ev) {
+            return self.on_vim_action(action);
+        }
+        match ev {
+            Event::Keyboard(key) => match actions::dispatch(Context::TodoList, &key) {
+                Some(msg) => Some(msg),
+                None => self.maybe_scroll_todo_list(ev),
+            },
+            Event::User(AppEvent::Mouse(ev)) => self.on_mouse(ev),
+            _ => None,
+        }
     }
 }
 
-#[derive(MockComponent)]
-pub struct TodoList {
-    component: List,
-}
+impl TodoList {
+    fn vim_action(&mut self, ev: &Event<AppEvent>) -> Option<VimAction> {
+        match (self.vim.as_mut(), ev) {
+            (Some(vim), Event::Keyboard(key)) => match vim.feed(*key) {
+                VimAction::Unhandled => None,
+                action => Some(action),
+            },
+            _ => None,
+        }
+    }
 
-impl Default for TodoList {
-    fn default() -> Self {
-        Self {
-            component: List::default()
-                .title("Item List", Alignment::Left)
-                .highlighted_color(Color::LightYellow)
-                .highlighted_str("👉")
-                .scroll(true)
-                .rewind(true)
-                .borders(
-                    Borders::default()
-                        .modifiers(BorderType::Double)
-                        .color(Color::Yellow),
-                ),
+    fn on_vim_action(&mut self, action: VimAction) -> Option<Msg> {
+        match action {
+            VimAction::FocusLeft => Some(Msg::TodoListBlur),
+            VimAction::Cut => Some(Msg::CutTodo),
+            VimAction::Copy => Some(Msg::CopyTodo),
+            VimAction::Paste => Some(Msg::PasteTodo),
+            VimAction::Repeat => Some(Msg::RepeatLastAction),
+            _ => match vim_motion(&mut self.component, action) {
+                Changed(state) => Some(Msg::TodoSelected(state.unwrap_one().unwrap_usize())),
+                _ => None,
+            },
         }
     }
-}
 
-impl Component<Msg, AppEvent> for TodoList {
-    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
-        match ev {
-            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => Some(Msg::TodoListBlur),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('e'),
-                ..
-            }) => Some(Msg::EditTodo),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                ..
-            }) => Some(Msg::AddTodo),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('d'),
-                ..
-            }) => Some(Msg::RemoveTodo),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char(' '),
-                ..
-            }) => Some(Msg::SwitchTodoStatus),
-            Event::Keyboard(KeyEvent { code: _, .. }) => self.maybe_scroll_todo_list(ev),
-            _ => Some(Msg::None),
+    fn on_mouse(&mut self, ev: MouseEvent) -> Option<Msg> {
+        let current = self.component.state().unwrap_one().unwrap_usize();
+        match self.clicks.gesture(&self.component, &ev)? {
+            Gesture::Click(index) | Gesture::DoubleClick(index) if self.on_status(&ev) => {
+                select_row(&mut self.component, index);
+                Some(Msg::TodoStatusClicked(index))
+            }
+            Gesture::Click(index) => {
+                select_row(&mut self.component, index);
+                Some(Msg::TodoClicked(index))
+            }
+            Gesture::DoubleClick(_) => Some(Msg::EditTodo),
+            Gesture::Focus => Some(Msg::TodoClicked(current)),
+            gesture => match scroll_row(&mut self.component, gesture) {
+                Changed(state) => Some(Msg::TodoSelected(state.unwrap_one().unwrap_usize())),
+                _ => None,
+            },
         }
     }
-}
 
-impl TodoList {
+    /// Whether the cursor is over the status marker, right after the highlight symbol
+    fn on_status(&self, ev: &MouseEvent) -> bool {
+        let start = self.component.inner().x + self.theme.highlight_str.width() as u16;
+        let end = start + status_width(&self.theme) as u16;
+        (start..end).contains(&ev.column)
+    }
+
     fn maybe_scroll_todo_list(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
         if let Changed(state) = maybe_scroll_list(&mut self.component, ev) {
             return Some(Msg::TodoSelected(state.unwrap_one().unwrap_usize()));
@@ -227,24 +319,39 @@ impl TodoList {
         None
     }
 
-    pub fn build_table_todo(todos: Vec<Todo>) -> Table {
+    /// Matches of `query` are highlighted
+    /// Rows in `marked` show their status reversed, those in `selected` a star
+    pub fn build_table_todo(
+        todos: Vec<Todo>,
+        query: Option<&str>,
+        marked: Option<RangeInclusive<usize>>,
+        selected: &[usize],
+        theme: &Theme,
+    ) -> Table {
         if todos.is_empty() {
             return vec![];
         }
         let mut table = TableBuilder::default();
+        let width = status_width(theme);
 
         todos.iter().enumerate().for_each(|(index, todo)| {
-            let (done, space) = match todo.done().unwrap() {
-                Some(true) => ("✔️", "  "),
-                Some(false) => ("❌", " "),
-                None => ("❓", " "),
-            };
-
+            let done = theme.symbols.status(todo.done().unwrap());
             let description = todo.description().unwrap();
+            let status = TextSpan::from(pad_to_width(done, width));
             let row = table
-                .add_col(TextSpan::from(done))
-                .add_col(TextSpan::from(space))
-                .add_col(TextSpan::from(description));
+                .add_col(
+                    match marked.as_ref().is_some_and(|range| range.contains(&index)) {
+                        true => status.reversed(),
+                        false => status,
+                    },
+                )
+                .add_col(match selected.contains(&index) {
+                    true => TextSpan::from("*").fg(theme.warning).bold(),
+                    false => TextSpan::from(" "),
+                });
+            for span in matched_spans(&description, query, theme) {
+                row.add_col(span);
+            }
 
             if index < todos.len() - 1 {
                 row.add_row();
@@ -260,7 +367,7 @@ pub enum EditPopupType {
 }
 #[derive(MockComponent)]
 pub struct EditPopup {
-    component: Input,
+    component: Clickable<Input>,
     edit_type: EditPopupType,
 }
 
@@ -289,13 +396,24 @@ impl Component<Msg, AppEvent> for EditPopup {
             }) => self.perform(Cmd::Delete),
             Event::Keyboard(KeyEvent {
                 code: Key::Char(ch),
-                modifiers: KeyModifiers::NONE,
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
             }) => self.perform(Cmd::Type(ch)),
 
             _ => CmdResult::None,
         };
         match ev {
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => match self.edit_type {
+            Event::Mouse(
+                ev @ MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    ..
+                },
+            ) if self.component.contains(&ev) => Some(Msg::None),
+            // A click outside the popup behaves like ESC
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. })
+            | Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                ..
+            }) => match self.edit_type {
                 EditPopupType::Note => Some(Msg::CloseEditNote(None)),
                 EditPopupType::Todo => Some(Msg::CloseEditTodo(None)),
             },
@@ -315,25 +433,239 @@ impl Component<Msg, AppEvent> for EditPopup {
 }
 
 impl EditPopup {
-    pub fn new(data: &str, title: &str, edit_type: EditPopupType) -> Self {
+    pub fn new(data: &str, title: &str, edit_type: EditPopupType, theme: &Theme) -> Self {
         EditPopup {
-            component: Input::default()
+            component: Clickable::new(
+                Input::default()
+                    .borders(
+                        Borders::default()
+                            .modifiers(theme.popup_border_type)
+                            .color(theme.popup_border),
+                    )
+                    .foreground(theme.popup_text)
+                    .input_type(InputType::Text)
+                    .title(title, Alignment::Left)
+                    .value(data)
+                    .invalid_style(Style::default().fg(theme.error)),
+            ),
+            edit_type,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Notification drawn over the lists, it never takes the focus
+#[derive(MockComponent)]
+pub struct Toast {
+    component: Paragraph,
+}
+
+impl Toast {
+    pub fn new(severity: Severity, text: &str, theme: &Theme) -> Self {
+        let (title, color) = match severity {
+            Severity::Warning => ("Warning", theme.warning),
+            Severity::Error => ("Error", theme.error),
+        };
+        Self {
+            component: Paragraph::default()
                 .borders(
                     Borders::default()
-                        .modifiers(BorderType::Rounded)
-                        .color(Color::LightYellow),
+                        .modifiers(theme.popup_border_type)
+                        .color(color),
                 )
-                .foreground(Color::LightYellow)
-                .input_type(InputType::Text)
+                .foreground(color)
                 .title(title, Alignment::Left)
-                .value(data)
-                .invalid_style(Style::default().fg(Color::Red)),
-            edit_type,
+                .wrap(true)
+                .text([TextSpan::from(text)]),
+        }
+    }
+}
+
+impl Component<Msg, AppEvent> for Toast {
+    fn on(&mut self, _ev: Event<AppEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// Question answered with y or n before an action that can't be undone
+#[derive(MockComponent)]
+pub struct Confirm {
+    component: Paragraph,
+    yes: Msg,
+    no: Msg,
+}
+
+impl Confirm {
+    pub fn new(question: &str, yes: Msg, no: Msg, theme: &Theme) -> Self {
+        Self {
+            component: Paragraph::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(theme.popup_border_type)
+                        .color(theme.popup_border),
+                )
+                .foreground(theme.popup_text)
+                .title("Confirm (y yes, n no)", Alignment::Left)
+                .wrap(true)
+                .text([TextSpan::from(question)]),
+            yes,
+            no,
+        }
+    }
+}
+
+impl Component<Msg, AppEvent> for Confirm {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y') | Key::Enter,
+                ..
+            }) => Some(Msg::CloseConfirm(Box::new(self.yes.clone()))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n') | Key::Char('q') | Key::Esc,
+                ..
+            }) => Some(Msg::CloseConfirm(Box::new(self.no.clone()))),
+            _ => None,
+        }
+    }
+}
+
+/// Select the given row clamping it inside the list boundaries
+fn select_row<C: MockComponent>(list: &mut C, index: usize) -> CmdResult {
+    let len = list
+        .query(Attribute::Content)
+        .map_or(0, |content| content.unwrap_table().len());
+    if len == 0 {
+        return CmdResult::None;
+    }
+    let index = index.min(len - 1);
+    if list.state().unwrap_one().unwrap_usize() == index {
+        return CmdResult::None;
+    }
+    list.attr(
+        Attribute::Value,
+        AttrValue::Payload(PropPayload::One(PropValue::Usize(index))),
+    );
+    CmdResult::Changed(list.state())
+}
+
+fn scroll_row<C: MockComponent>(list: &mut C, gesture: Gesture) -> CmdResult {
+    let current = list.state().unwrap_one().unwrap_usize();
+    match gesture {
+        Gesture::ScrollDown => select_row(list, current + 1),
+        Gesture::ScrollUp if current > 0 => select_row(list, current - 1),
+        _ => CmdResult::None,
+    }
+}
+
+/// Width of the widest status marker
+fn status_width(theme: &Theme) -> usize {
+    [Some(true), Some(false), None]
+        .iter()
+        .map(|done| theme.symbols.status(*done).width())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Spans of `text` with the first match of `query` highlighted
+fn matched_spans(text: &str, query: Option<&str>, theme: &Theme) -> Vec<TextSpan> {
+    let indices = query
+        .and_then(|query| finder::substring_indices(query, text))
+        .unwrap_or_default();
+    finder::highlight_spans(text, &indices, theme.highlight)
+}
+
+/// Pad `text` with spaces up to `width` terminal cells
+fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{text}{}", " ".repeat(width.saturating_sub(text.width())))
+}
+
+fn vim_keys(key_mode: KeyMode) -> Option<VimKeys> {
+    match key_mode {
+        KeyMode::Standard => None,
+        KeyMode::Vim => Some(VimKeys::default()),
+    }
+}
+
+/// Scrollable list of the read only popups, closed with ESC, q, their own key or a click
+/// outside
+#[derive(MockComponent)]
+pub struct PopupList {
+    component: Clickable<List>,
+}
+
+impl PopupList {
+    pub fn new(list: List) -> Self {
+        Self {
+            component: Clickable::new(list),
+        }
+    }
+
+    /// List with the popup borders and colors, to be customized further
+    pub fn list(title: impl Into<String>, rows: Table, theme: &Theme) -> List {
+        List::default()
+            .title(title, Alignment::Left)
+            .borders(
+                Borders::default()
+                    .modifiers(theme.popup_border_type)
+                    .color(theme.popup_border),
+            )
+            .foreground(theme.popup_text)
+            .highlighted_color(theme.highlight)
+            .scroll(true)
+            .step(8)
+            .rows(rows)
+    }
+
+    pub fn contains(&self, ev: &MouseEvent) -> bool {
+        self.component.contains(ev)
+    }
+
+    /// `close` on ESC, q, `key` or a click outside, anything else scrolls the list
+    pub fn on_event(&mut self, ev: Event<AppEvent>, key: char, close: Msg) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Char('q'),
+                ..
+            }) => Some(close),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(code),
+                ..
+            }) if code == key => Some(close),
+            Event::Mouse(
+                ev @ MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    ..
+                },
+            ) if !self.contains(&ev) => Some(close),
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollDown,
+                ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            ev => {
+                maybe_scroll_list(&mut self.component, ev);
+                Some(Msg::None)
+            }
         }
     }
 }
 
-fn maybe_scroll_list(list: &mut List, ev: Event<AppEvent>) -> CmdResult {
+fn maybe_scroll_list<C: MockComponent>(list: &mut C, ev: Event<AppEvent>) -> CmdResult {
     match ev {
         Event::Keyboard(KeyEvent {
             code: Key::Down, ..
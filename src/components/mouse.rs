@@ -0,0 +1,151 @@
+use std::time::{Duration, Instant};
+
+use tui_realm_stdlib::Phantom;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State,
+    command::{Cmd, CmdResult},
+    event::{MouseButton, MouseEvent, MouseEventKind},
+    ratatui::layout::{Margin, Position, Rect},
+};
+
+use crate::{AppEvent, Msg};
+
+static DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Wrapper remembering where the inner component has been drawn, used to route mouse events
+pub struct Clickable<C: MockComponent> {
+    component: C,
+    area: Rect,
+}
+
+impl<C: MockComponent> Clickable<C> {
+    pub fn new(component: C) -> Self {
+        Self {
+            component,
+            area: Rect::default(),
+        }
+    }
+
+    pub fn contains(&self, ev: &MouseEvent) -> bool {
+        self.area.contains(Position::new(ev.column, ev.row))
+    }
+
+    /// Area inside the borders
+    pub fn inner(&self) -> Rect {
+        self.area.inner(Margin::new(1, 1))
+    }
+
+    /// Index of the list row under the cursor, taking into account the rows scrolled away
+    pub fn row_at(&self, ev: &MouseEvent) -> Option<usize> {
+        let inner = self.inner();
+        if !inner.contains(Position::new(ev.column, ev.row)) {
+            return None;
+        }
+        let len = self
+            .query(Attribute::Content)
+            .map_or(0, |content| content.unwrap_table().len());
+        let selected = match self.state() {
+            State::One(value) => value.unwrap_usize(),
+            _ => 0,
+        };
+        let first_visible = selected.saturating_sub(inner.height.saturating_sub(1) as usize);
+        let index = first_visible + (ev.row - inner.y) as usize;
+        (index < len).then_some(index)
+    }
+}
+
+impl<C: MockComponent> MockComponent for Clickable<C> {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
+        self.component.view(frame, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.component.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
+}
+
+/// Hands the mouse events to the model, which sends them back to both lists as
+/// `AppEvent::Mouse`. Subscriptions can only match every event or exact mouse events
+#[derive(MockComponent, Default)]
+pub struct MouseRouter {
+    component: Phantom,
+}
+
+impl Component<Msg, AppEvent> for MouseRouter {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        match ev {
+            // Moves are not used and would redraw on every motion
+            Event::Mouse(
+                ev @ MouseEvent {
+                    kind:
+                        MouseEventKind::Down(MouseButton::Left)
+                        | MouseEventKind::ScrollUp
+                        | MouseEventKind::ScrollDown,
+                    ..
+                },
+            ) => Some(Msg::ForwardMouse(ev)),
+            _ => None,
+        }
+    }
+}
+
+/// Mouse gestures understood by the list components
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Gesture {
+    Click(usize),
+    DoubleClick(usize),
+    /// Click on the list but outside any row
+    Focus,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// Translate raw mouse events into gestures, keeping track of the last click
+#[derive(Default)]
+pub struct ClickTracker {
+    last_click: Option<(Instant, usize)>,
+}
+
+impl ClickTracker {
+    pub fn gesture<C: MockComponent>(
+        &mut self,
+        list: &Clickable<C>,
+        ev: &MouseEvent,
+    ) -> Option<Gesture> {
+        if !list.contains(ev) {
+            return None;
+        }
+        match ev.kind {
+            MouseEventKind::ScrollUp => Some(Gesture::ScrollUp),
+            MouseEventKind::ScrollDown => Some(Gesture::ScrollDown),
+            MouseEventKind::Down(MouseButton::Left) => match list.row_at(ev) {
+                Some(index) => {
+                    let now = Instant::now();
+                    let double = self.last_click.is_some_and(|(time, last)| {
+                        last == index && now.duration_since(time) < DOUBLE_CLICK
+                    });
+                    self.last_click = (!double).then_some((now, index));
+                    match double {
+                        true => Some(Gesture::DoubleClick(index)),
+                        false => Some(Gesture::Click(index)),
+                    }
+                }
+                None => Some(Gesture::Focus),
+            },
+            _ => None,
+        }
+    }
+}
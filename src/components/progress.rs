@@ -0,0 +1,131 @@
+use tui_realm_stdlib::ProgressBar;
+use tuirealm::{
+    Component, Event, MockComponent,
+    props::{Alignment, Borders},
+};
+
+use crate::{AppEvent, Msg, backend::Note, config::Theme};
+
+/// Cells of the gauge drawn in the note list
+static COMPACT_WIDTH: usize = 5;
+
+/// Done items out of the open and done ones, unknown items are counted apart
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub open: usize,
+    pub done: usize,
+    pub unknown: usize,
+}
+
+impl Progress {
+    pub fn of(note: &Note) -> Self {
+        note.todos()
+            .iter()
+            .fold(Self::default(), |mut progress, todo| {
+                match todo.done().unwrap_or_default() {
+                    Some(true) => progress.done += 1,
+                    Some(false) => progress.open += 1,
+                    None => progress.unknown += 1,
+                }
+                progress
+            })
+    }
+
+    pub fn total(&self) -> usize {
+        self.open + self.done
+    }
+
+    /// Zero without any open or done item
+    pub fn ratio(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.done as f64 / total as f64,
+        }
+    }
+
+    pub fn complete(&self) -> bool {
+        self.total() > 0 && self.open == 0
+    }
+
+    /// Gauge a few cells wide made of the theme symbols
+    pub fn compact(&self, theme: &Theme) -> String {
+        let full = (self.ratio() * COMPACT_WIDTH as f64).round() as usize;
+        theme.symbols.gauge_full.repeat(full)
+            + &theme.symbols.gauge_empty.repeat(COMPACT_WIDTH - full)
+    }
+
+    fn label(&self) -> String {
+        let label = format!(
+            "{}% - {} of {} done",
+            (self.ratio() * 100.0).round(),
+            self.done,
+            self.total()
+        );
+        match self.unknown {
+            0 => label,
+            unknown => format!("{label}, {unknown} unknown"),
+        }
+    }
+}
+
+/// Completion of the selected note, above its items
+#[derive(MockComponent)]
+pub struct ProgressGauge {
+    component: ProgressBar,
+}
+
+impl ProgressGauge {
+    pub fn new(progress: &Progress, theme: &Theme) -> Self {
+        let color = match progress.complete() {
+            true => theme.highlight,
+            false => theme.index,
+        };
+        Self {
+            component: ProgressBar::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(theme.border_type)
+                        .color(theme.border),
+                )
+                .title("Progress", Alignment::Left)
+                .foreground(color)
+                .label(progress.label())
+                .progress(progress.ratio()),
+        }
+    }
+}
+
+impl Component<Msg, AppEvent> for ProgressGauge {
+    fn on(&mut self, _ev: Event<AppEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progress;
+    use crate::config::Theme;
+
+    #[test]
+    fn progress_test() {
+        let progress = Progress {
+            open: 1,
+            done: 3,
+            unknown: 2,
+        };
+        assert_eq!(progress.ratio(), 0.75);
+        assert!(!progress.complete());
+        assert_eq!(progress.label(), "75% - 3 of 4 done, 2 unknown");
+        assert_eq!(progress.compact(&Theme::dark().ascii()), "####-");
+
+        // Unknown items don't count
+        let progress = Progress {
+            open: 0,
+            done: 0,
+            unknown: 2,
+        };
+        assert_eq!(progress.ratio(), 0.0);
+        assert!(!progress.complete());
+        assert_eq!(progress.compact(&Theme::dark().ascii()), "-----");
+    }
+}
@@ -0,0 +1,177 @@
+use chrono::TimeDelta;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State,
+    command::{Cmd, CmdResult},
+    event::{MouseButton, MouseEvent, MouseEventKind},
+    props::{Table, TableBuilder, TextSpan},
+    ratatui::{
+        layout::{Constraint, Layout, Position, Rect},
+        style::Style,
+        widgets::{Block, Sparkline},
+    },
+};
+
+use super::{PopupList, Progress, pad_to_width};
+use crate::{AppEvent, Msg, config::Theme};
+
+static SPARKLINE_HEIGHT: u16 = 6;
+static TITLE_WIDTH: usize = 24;
+
+/// Figures shown by the statistics view, see `model::stats`
+pub struct StatsData<'a> {
+    pub completions: &'a [u64],
+    pub notes: &'a [(String, Progress)],
+    pub average_completion: Option<TimeDelta>,
+    pub longest_open: &'a [(String, String, TimeDelta)],
+}
+
+/// Completions per day above the per note figures
+pub struct StatsView {
+    /// Drawn with the ratatui widget, the stdlib one scales the bars to the number of entries
+    sparkline: Sparkline<'static>,
+    details: PopupList,
+    /// Where the view was last drawn, clicks outside close it
+    area: Rect,
+}
+
+impl StatsView {
+    pub fn new(data: &StatsData, theme: &Theme) -> Self {
+        let total: u64 = data.completions.iter().sum();
+        Self {
+            sparkline: Sparkline::default()
+                .block(
+                    Block::bordered()
+                        .border_type(theme.popup_border_type)
+                        .border_style(Style::default().fg(theme.popup_border))
+                        .title(format!(
+                            "Completed per day, {} in the last {} days",
+                            total,
+                            data.completions.len()
+                        )),
+                )
+                .style(Style::default().fg(theme.highlight))
+                .data(data.completions),
+            details: PopupList::new(PopupList::list(
+                "Statistics (i or ESC to close)",
+                Self::build_table_stats(data, theme),
+                theme,
+            )),
+            area: Rect::default(),
+        }
+    }
+
+    fn build_table_stats(data: &StatsData, theme: &Theme) -> Table {
+        let mut table = TableBuilder::default();
+        let heading = |table: &mut TableBuilder, text: &str| {
+            table
+                .add_col(TextSpan::from(text).fg(theme.highlight).bold())
+                .add_row();
+        };
+
+        table
+            .add_col(TextSpan::from("Average time to completion: "))
+            .add_col(TextSpan::from(
+                data.average_completion
+                    .map_or("-".to_string(), format_duration),
+            ))
+            .add_row()
+            .add_col(TextSpan::from(""))
+            .add_row();
+
+        heading(&mut table, "Open and done per note");
+        for (title, progress) in data.notes {
+            table
+                .add_col(TextSpan::from(format!(" {} ", progress.compact(theme))).fg(theme.index))
+                .add_col(TextSpan::from(pad_to_width(title, TITLE_WIDTH)))
+                .add_col(TextSpan::from(format!(
+                    " {} open, {} done",
+                    progress.open, progress.done
+                )))
+                .add_row();
+        }
+        table.add_col(TextSpan::from("")).add_row();
+
+        heading(&mut table, "Open for the longest");
+        if data.longest_open.is_empty() {
+            table.add_col(TextSpan::from(" -")).add_row();
+        }
+        for (title, description, age) in data.longest_open {
+            table
+                .add_col(TextSpan::from(format!(" {:>7} ", format_duration(*age))).fg(theme.index))
+                .add_col(TextSpan::from(format!("{}: ", title)).italic())
+                .add_col(TextSpan::from(description))
+                .add_row();
+        }
+        table.build()
+    }
+
+    fn contains(&self, ev: &MouseEvent) -> bool {
+        self.area.contains(Position::new(ev.column, ev.row))
+    }
+}
+
+/// Two most significant units, as "3d 4h" or "12m"
+fn format_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, hours) => format!("{}h {}m", hours, minutes),
+        (days, hours) => format!("{}d {}h", days, hours),
+    }
+}
+
+impl MockComponent for StatsView {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
+        let chunks = Layout::vertical([Constraint::Length(SPARKLINE_HEIGHT), Constraint::Min(0)])
+            .split(area);
+        frame.render_widget(&self.sparkline, chunks[0]);
+        self.details.view(frame, chunks[1]);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.details.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.details.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.details.perform(cmd)
+    }
+}
+
+impl Component<Msg, AppEvent> for StatsView {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        match ev {
+            // The sparkline is part of the view too
+            Event::Mouse(
+                ev @ MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    ..
+                },
+            ) if self.contains(&ev) => Some(Msg::None),
+            ev => self.details.on_event(ev, 'i', Msg::CloseStats),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::format_duration;
+
+    #[test]
+    fn format_duration_test() {
+        assert_eq!(format_duration(TimeDelta::seconds(30)), "0m");
+        assert_eq!(format_duration(TimeDelta::minutes(125)), "2h 5m");
+        assert_eq!(format_duration(TimeDelta::hours(50)), "2d 2h");
+    }
+}
@@ -0,0 +1,111 @@
+use tui_realm_stdlib::Span;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State,
+    command::{Cmd, CmdResult},
+    props::{Alignment, TextSpan},
+    ratatui::layout::{Constraint, Direction, Layout, Rect},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{AppEvent, Msg, config::Theme};
+
+/// What the status bar tells about the wall and the selected note
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    pub data_dir: String,
+    pub notes: usize,
+    pub open: usize,
+    pub done: usize,
+    pub unknown: usize,
+    /// Todos of the note marked for the bulk actions
+    pub selected: usize,
+    /// Which todos are listed, `None` when all of them
+    pub filter: Option<String>,
+    /// Order of the notes, `None` when manual
+    pub sort: Option<String>,
+    /// Transient feedback about the last action
+    pub message: Option<String>,
+}
+
+/// Bottom line with the context on the left and the last message on the right
+pub struct StatusBar {
+    context: Span,
+    message: Span,
+    message_width: u16,
+}
+
+impl StatusBar {
+    pub fn new(status: &Status, theme: &Theme) -> Self {
+        let separator = || TextSpan::from(" | ");
+        let message = status.message.clone().unwrap_or_default();
+        let mut context = vec![
+            TextSpan::from(format!(" {}", status.data_dir)).fg(theme.index),
+            separator(),
+            TextSpan::from(match status.notes {
+                1 => "1 note".to_string(),
+                notes => format!("{} notes", notes),
+            }),
+            separator(),
+            TextSpan::from(format!("{} open", status.open)),
+            TextSpan::from(", "),
+            TextSpan::from(format!("{} done", status.done)),
+            TextSpan::from(", "),
+            TextSpan::from(format!("{} unknown", status.unknown)),
+        ];
+        if status.selected > 0 {
+            context.push(separator());
+            context
+                .push(TextSpan::from(format!("{} selected", status.selected)).fg(theme.highlight));
+        }
+        if let Some(sort) = &status.sort {
+            context.push(separator());
+            context.push(TextSpan::from(format!("by {}", sort)));
+        }
+        if let Some(filter) = &status.filter {
+            context.push(separator());
+            context.push(TextSpan::from(filter).fg(theme.warning));
+        }
+        context.push(separator());
+        context.push(TextSpan::from("? help").fg(theme.index));
+        Self {
+            context: Span::default().spans(context),
+            message_width: message.width() as u16 + 1,
+            message: Span::default()
+                .alignment(Alignment::Right)
+                .spans([TextSpan::from(message).fg(theme.highlight).bold()]),
+        }
+    }
+}
+
+impl MockComponent for StatusBar {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(self.message_width)])
+            .split(area);
+        self.context.view(frame, chunks[0]);
+        self.message.view(frame, chunks[1]);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.context.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.context.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _cmd: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, AppEvent> for StatusBar {
+    fn on(&mut self, _ev: Event<AppEvent>) -> Option<Msg> {
+        None
+    }
+}
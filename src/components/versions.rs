@@ -0,0 +1,103 @@
+use tuirealm::{
+    Component, Event, MockComponent, State,
+    event::{Key, KeyEvent},
+    props::{TableBuilder, TextSpan},
+};
+
+use super::PopupList;
+use crate::{AppEvent, Msg, backend::Version, config::Theme};
+
+/// Committed versions of a note, or the changes made since one of them
+#[derive(MockComponent)]
+pub struct Versions {
+    component: PopupList,
+    /// Index of the version whose changes are shown, `None` while listing the versions
+    diff: Option<usize>,
+}
+
+impl Versions {
+    pub fn new(title: &str, versions: &[Version], selected: usize, theme: &Theme) -> Self {
+        let mut table = TableBuilder::default();
+        for version in versions {
+            table
+                .add_col(TextSpan::from(format!("{}  ", version.time)).fg(theme.index))
+                .add_col(TextSpan::from(&version.summary))
+                .add_row();
+        }
+        let rows = match versions.is_empty() {
+            true => vec![vec![TextSpan::from("Nothing committed yet")]],
+            false => table.build(),
+        };
+        Self {
+            component: PopupList::new(
+                PopupList::list(
+                    format!(
+                        "Versions of \"{}\" (ENTER changes, r restore, ESC close)",
+                        title
+                    ),
+                    rows,
+                    theme,
+                )
+                .highlighted_str(&theme.highlight_str)
+                .selected_line(selected.min(versions.len().saturating_sub(1))),
+            ),
+            diff: None,
+        }
+    }
+
+    /// Changes from the version at `index` to the current note, as `git diff` prints them
+    pub fn diff(version: &Version, index: usize, diff: &str, theme: &Theme) -> Self {
+        let mut table = TableBuilder::default();
+        // The file header repeats what the title says
+        for line in diff.lines().skip_while(|line| !line.starts_with("@@")) {
+            let color = match line.chars().next() {
+                Some('+') => theme.highlight,
+                Some('-') => theme.error,
+                Some('@') => theme.index,
+                _ => theme.popup_text,
+            };
+            table.add_col(TextSpan::from(line).fg(color)).add_row();
+        }
+        let rows = match diff.is_empty() {
+            true => vec![vec![TextSpan::from("Same as the current version")]],
+            false => table.build(),
+        };
+        Self {
+            component: PopupList::new(PopupList::list(
+                format!("Changes since {} (r restore, ESC back)", version.time),
+                rows,
+                theme,
+            )),
+            diff: Some(index),
+        }
+    }
+
+    fn selected(&self) -> usize {
+        match self.diff {
+            Some(index) => index,
+            None => match self.state() {
+                State::One(value) => value.unwrap_usize(),
+                _ => 0,
+            },
+        }
+    }
+}
+
+impl Component<Msg, AppEvent> for Versions {
+    fn on(&mut self, ev: Event<AppEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Char('q'),
+                ..
+            }) if self.diff.is_some() => Some(Msg::ShowVersions(self.selected())),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) if self.diff.is_none() => Some(Msg::ShowVersionDiff(self.selected())),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('r'),
+                ..
+            }) => Some(Msg::ConfirmRestoreVersion(self.selected())),
+            ev => self.component.on_event(ev, 'V', Msg::CloseVersions),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use tuirealm::{
+    Attribute, MockComponent,
+    command::CmdResult,
+    event::{Key, KeyEvent, KeyModifiers},
+};
+
+use super::select_row;
+
+/// Actions produced by the vim key sequence parser
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VimAction {
+    /// The key has been consumed, waiting for the rest of the sequence
+    Pending,
+    Down(usize),
+    Up(usize),
+    Top,
+    Bottom,
+    Line(usize),
+    FocusLeft,
+    FocusRight,
+    Cut,
+    Copy,
+    Paste,
+    Repeat,
+    /// The key is not part of a vim sequence, fallback to the standard bindings
+    Unhandled,
+}
+
+/// Stateful parser for vim style key sequences (`5j`, `gg`, `dd`...)
+#[derive(Default)]
+pub struct VimKeys {
+    count: Option<usize>,
+    pending: Option<char>,
+}
+
+impl VimKeys {
+    pub fn feed(&mut self, key: KeyEvent) -> VimAction {
+        let ch = match key {
+            KeyEvent {
+                code: Key::Char(ch),
+                modifiers,
+            } if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => ch,
+            _ => {
+                self.reset();
+                return VimAction::Unhandled;
+            }
+        };
+
+        if let Some(pending) = self.pending.take() {
+            let count = self.count.take();
+            return match (pending, ch) {
+                ('g', 'g') => count.map_or(VimAction::Top, VimAction::Line),
+                ('d', 'd') => VimAction::Cut,
+                ('y', 'y') => VimAction::Copy,
+                _ => VimAction::Pending,
+            };
+        }
+
+        match ch {
+            '1'..='9' => {
+                self.push_digit(ch);
+                VimAction::Pending
+            }
+            '0' if self.count.is_some() => {
+                self.push_digit(ch);
+                VimAction::Pending
+            }
+            'g' | 'd' | 'y' => {
+                self.pending = Some(ch);
+                VimAction::Pending
+            }
+            _ => {
+                let count = self.count.take();
+                match ch {
+                    'j' => VimAction::Down(count.unwrap_or(1)),
+                    'k' => VimAction::Up(count.unwrap_or(1)),
+                    'G' => count.map_or(VimAction::Bottom, VimAction::Line),
+                    'h' => VimAction::FocusLeft,
+                    'l' => VimAction::FocusRight,
+                    'p' => VimAction::Paste,
+                    '.' => VimAction::Repeat,
+                    _ => VimAction::Unhandled,
+                }
+            }
+        }
+    }
+
+    fn push_digit(&mut self, ch: char) {
+        let digit = ch.to_digit(10).unwrap_or_default() as usize;
+        self.count = Some(
+            self.count
+                .unwrap_or_default()
+                .saturating_mul(10)
+                .saturating_add(digit),
+        );
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.pending = None;
+    }
+}
+
+/// Apply a vim motion to the list, clamping at the boundaries instead of rewinding
+pub fn vim_motion<C: MockComponent>(list: &mut C, action: VimAction) -> CmdResult {
+    let len = list
+        .query(Attribute::Content)
+        .map_or(0, |content| content.unwrap_table().len());
+    if len == 0 {
+        return CmdResult::None;
+    }
+    let current = list.state().unwrap_one().unwrap_usize();
+    let target = match action {
+        VimAction::Down(count) => current.saturating_add(count),
+        VimAction::Up(count) => current.saturating_sub(count),
+        VimAction::Top => 0,
+        VimAction::Bottom => len - 1,
+        VimAction::Line(line) => line.saturating_sub(1),
+        _ => return CmdResult::None,
+    };
+    select_row(list, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+    use super::{VimAction, VimKeys};
+
+    fn feed_all(vim: &mut VimKeys, keys: &str) -> Vec<VimAction> {
+        keys.chars()
+            .map(|ch| vim.feed(KeyEvent::new(Key::Char(ch), KeyModifiers::NONE)))
+            .collect()
+    }
+
+    #[test]
+    fn sequences_test() {
+        let mut vim = VimKeys::default();
+
+        assert_eq!(feed_all(&mut vim, "j"), vec![VimAction::Down(1)]);
+        assert_eq!(
+            feed_all(&mut vim, "12k"),
+            vec![VimAction::Pending, VimAction::Pending, VimAction::Up(12)]
+        );
+        assert_eq!(
+            feed_all(&mut vim, "gg"),
+            vec![VimAction::Pending, VimAction::Top]
+        );
+        assert_eq!(
+            feed_all(&mut vim, "10G"),
+            vec![VimAction::Pending, VimAction::Pending, VimAction::Line(10)]
+        );
+        assert_eq!(feed_all(&mut vim, "G"), vec![VimAction::Bottom]);
+        assert_eq!(
+            feed_all(&mut vim, "dd"),
+            vec![VimAction::Pending, VimAction::Cut]
+        );
+        assert_eq!(
+            feed_all(&mut vim, "yy"),
+            vec![VimAction::Pending, VimAction::Copy]
+        );
+
+        // An unknown second key aborts the pending sequence
+        assert_eq!(
+            feed_all(&mut vim, "dyj"),
+            vec![VimAction::Pending, VimAction::Pending, VimAction::Down(1)]
+        );
+
+        // Non vim keys reset the count and fallback to the standard bindings
+        assert_eq!(feed_all(&mut vim, "3"), vec![VimAction::Pending]);
+        assert_eq!(
+            vim.feed(KeyEvent::new(Key::Enter, KeyModifiers::NONE)),
+            VimAction::Unhandled
+        );
+        assert_eq!(feed_all(&mut vim, "j"), vec![VimAction::Down(1)]);
+        assert_eq!(feed_all(&mut vim, "e"), vec![VimAction::Unhandled]);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{collections::BTreeMap, fs::read_to_string, path::Path, time::Duration};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::{KeyMode, SortMode};
+
+pub use symbols::{Symbols, SymbolsMode};
+pub use theme::Theme;
+use theme::{CustomTheme, DARK, HIGH_CONTRAST, LIGHT};
+
+mod symbols;
+mod theme;
+
+static CONFIG_FILE: &str = "config.toml";
+static INVALID_CONFIG: &str = "Invalid configuration file";
+
+/// Settings read from `config.toml` inside the data directory
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    vim: bool,
+    theme: String,
+    symbols: SymbolsMode,
+    sort: SortMode,
+    /// Days after which a note with every item done is archived, never when missing
+    archive_after_days: Option<u64>,
+    /// Commit every change of the data directory to a git repository
+    git: bool,
+    themes: BTreeMap<String, CustomTheme>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            vim: false,
+            theme: DARK.to_string(),
+            symbols: Default::default(),
+            sort: Default::default(),
+            archive_after_days: None,
+            git: false,
+            themes: Default::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the configuration file, a missing file means default settings
+    pub fn load(folder_path: &Path) -> Result<Self> {
+        let path = folder_path.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::parse(&read_to_string(&path).context(INVALID_CONFIG)?)
+            .with_context(|| format!("{INVALID_CONFIG} {}", path.display()))
+    }
+
+    fn parse(data: &str) -> Result<Self> {
+        let config: Config = toml::from_str(data)?;
+        for name in config.themes.keys() {
+            config.resolve_theme(name)?;
+        }
+        config.theme()?;
+        Ok(config)
+    }
+
+    pub fn key_mode(&self) -> KeyMode {
+        match self.vim {
+            true => KeyMode::Vim,
+            false => KeyMode::Standard,
+        }
+    }
+
+    pub fn set_key_mode(&mut self, key_mode: KeyMode) {
+        self.vim = key_mode == KeyMode::Vim;
+    }
+
+    pub fn sort_mode(&self) -> SortMode {
+        self.sort
+    }
+
+    pub fn archive_after(&self) -> Option<Duration> {
+        self.archive_after_days
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+    }
+
+    pub fn git(&self) -> bool {
+        self.git
+    }
+
+    pub fn set_git(&mut self, git: bool) {
+        self.git = git;
+    }
+
+    pub fn set_theme(&mut self, name: &str) -> Result<()> {
+        let previous = std::mem::replace(&mut self.theme, name.to_string());
+        if let Err(err) = self.theme() {
+            self.theme = previous;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    pub fn set_symbols_mode(&mut self, symbols: SymbolsMode) {
+        self.symbols = symbols;
+    }
+
+    /// Built-in themes followed by the user defined ones
+    pub fn theme_names(&self) -> Vec<String> {
+        [DARK, LIGHT, HIGH_CONTRAST]
+            .into_iter()
+            .map(str::to_string)
+            .chain(self.themes.keys().cloned())
+            .collect()
+    }
+
+    pub fn theme(&self) -> Result<Theme> {
+        self.named_theme(&self.theme)
+    }
+
+    /// Any theme of `theme_names`, with the configured symbols
+    pub fn named_theme(&self, name: &str) -> Result<Theme> {
+        let theme = self.resolve_theme(name)?;
+        match self.symbols.ascii() {
+            true => Ok(theme.ascii()),
+            false => Ok(theme),
+        }
+    }
+
+    fn resolve_theme(&self, name: &str) -> Result<Theme> {
+        if let Some(custom) = self.themes.get(name) {
+            let Some(base) = Theme::builtin(custom.base()) else {
+                bail!("Unknown base theme {} for {name}", custom.base())
+            };
+            return custom
+                .apply(base)
+                .with_context(|| format!("Invalid theme {name}"));
+        }
+        Theme::builtin(name).with_context(|| format!("Unknown theme {name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tuirealm::props::{BorderType, Color};
+
+    use super::{Config, Symbols, SymbolsMode, Theme};
+    use crate::{KeyMode, SortMode};
+
+    #[test]
+    fn themes_test() {
+        let config = Config::parse("symbols = \"unicode\"").unwrap();
+        assert_eq!(config.key_mode(), KeyMode::Standard);
+        assert_eq!(config.theme().unwrap(), Theme::dark());
+
+        let mut config = Config::parse(
+            r##"
+            vim = true
+            theme = "ocean"
+            symbols = "unicode"
+            sort = "open-todos"
+            archive_after_days = 2
+            git = true
+
+            [themes.ocean]
+            base = "light"
+            border = "#102030"
+            border_type = "thick"
+            highlight_str = ">"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.key_mode(), KeyMode::Vim);
+        assert_eq!(config.sort_mode(), SortMode::OpenTodos);
+        assert_eq!(config.archive_after(), Some(Duration::from_secs(172_800)));
+        assert!(config.git());
+        let theme = config.theme().unwrap();
+        assert_eq!(theme.border, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(theme.border_type, BorderType::Thick);
+        assert_eq!(theme.highlight_str, ">");
+        assert_eq!(theme.highlight, Theme::light().highlight);
+
+        assert!(config.set_theme("high-contrast").is_ok());
+        assert_eq!(config.theme().unwrap(), Theme::high_contrast());
+        assert!(config.set_theme("missing").is_err());
+        assert_eq!(config.theme().unwrap(), Theme::high_contrast());
+        assert_eq!(config.theme_names().last().unwrap(), "ocean");
+        assert_eq!(config.named_theme("light").unwrap(), Theme::light());
+
+        config.set_symbols_mode(SymbolsMode::Ascii);
+        let theme = config.theme().unwrap();
+        assert_eq!(theme.symbols, Symbols::ascii());
+        assert_eq!(theme.highlight_str, ">");
+
+        assert!(Config::parse("theme = \"missing\"").is_err());
+        assert!(Config::parse("symbols = \"emoji\"").is_err());
+        assert!(Config::parse("sort = \"random\"").is_err());
+        assert!(Config::parse("[themes.bad]\nborder = \"not a color\"\n").is_err());
+        assert!(Config::parse("[themes.bad]\nbase = \"missing\"\n").is_err());
+    }
+}
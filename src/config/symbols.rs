@@ -0,0 +1,84 @@
+use std::env::var;
+
+use serde::Deserialize;
+
+/// Markers drawn next to the todos and the notes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symbols {
+    pub done: String,
+    pub undone: String,
+    pub unknown: String,
+    pub pin: String,
+    pub gauge_full: String,
+    pub gauge_empty: String,
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        Self::unicode()
+    }
+}
+
+impl Symbols {
+    pub fn unicode() -> Self {
+        Self {
+            done: "✔️".to_string(),
+            undone: "❌".to_string(),
+            unknown: "❓".to_string(),
+            pin: "📌".to_string(),
+            gauge_full: "█".to_string(),
+            gauge_empty: "░".to_string(),
+        }
+    }
+
+    pub fn ascii() -> Self {
+        Self {
+            done: "[x]".to_string(),
+            undone: "[ ]".to_string(),
+            unknown: "[?]".to_string(),
+            pin: "^".to_string(),
+            gauge_full: "#".to_string(),
+            gauge_empty: "-".to_string(),
+        }
+    }
+
+    pub fn status(&self, done: Option<bool>) -> &str {
+        match done {
+            Some(true) => &self.done,
+            Some(false) => &self.undone,
+            None => &self.unknown,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolsMode {
+    /// Unicode when the locale is UTF-8, ASCII otherwise
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+impl SymbolsMode {
+    pub fn ascii(&self) -> bool {
+        match self {
+            SymbolsMode::Auto => !utf8_locale(),
+            SymbolsMode::Unicode => false,
+            SymbolsMode::Ascii => true,
+        }
+    }
+}
+
+/// Mimic the libc lookup order: the first non empty among LC_ALL, LC_CTYPE and LANG wins
+fn utf8_locale() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(|name| var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
@@ -0,0 +1,162 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use tuirealm::props::{BorderType, Color};
+
+use super::Symbols;
+
+pub const DARK: &str = "dark";
+pub const LIGHT: &str = "light";
+pub const HIGH_CONTRAST: &str = "high-contrast";
+
+/// Colours and decorations shared by every component
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub border: Color,
+    pub border_type: BorderType,
+    pub highlight: Color,
+    pub highlight_str: String,
+    pub index: Color,
+    pub popup_border: Color,
+    pub popup_border_type: BorderType,
+    pub popup_text: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub symbols: Symbols,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            border: Color::Yellow,
+            border_type: BorderType::Double,
+            highlight: Color::LightYellow,
+            highlight_str: "👉".to_string(),
+            index: Color::Cyan,
+            popup_border: Color::LightYellow,
+            popup_border_type: BorderType::Rounded,
+            popup_text: Color::LightYellow,
+            error: Color::Red,
+            warning: Color::Yellow,
+            symbols: Symbols::unicode(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            border: Color::Blue,
+            border_type: BorderType::Rounded,
+            highlight: Color::Magenta,
+            highlight_str: "👉".to_string(),
+            index: Color::DarkGray,
+            popup_border: Color::Magenta,
+            popup_border_type: BorderType::Rounded,
+            popup_text: Color::Black,
+            error: Color::Red,
+            warning: Color::Yellow,
+            symbols: Symbols::unicode(),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            border: Color::White,
+            border_type: BorderType::Thick,
+            highlight: Color::LightYellow,
+            highlight_str: "▶".to_string(),
+            index: Color::White,
+            popup_border: Color::White,
+            popup_border_type: BorderType::Thick,
+            popup_text: Color::White,
+            error: Color::LightRed,
+            warning: Color::LightYellow,
+            symbols: Symbols::unicode(),
+        }
+    }
+
+    /// Replace every non ASCII glyph with a plain ASCII one
+    pub fn ascii(mut self) -> Self {
+        self.symbols = Symbols::ascii();
+        if !self.highlight_str.is_ascii() {
+            self.highlight_str = ">".to_string();
+        }
+        self
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            DARK => Some(Self::dark()),
+            LIGHT => Some(Self::light()),
+            HIGH_CONTRAST => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+}
+
+/// User defined theme, every missing entry is inherited from `base`
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomTheme {
+    base: Option<String>,
+    border: Option<String>,
+    border_type: Option<String>,
+    highlight: Option<String>,
+    highlight_str: Option<String>,
+    index: Option<String>,
+    popup_border: Option<String>,
+    popup_border_type: Option<String>,
+    popup_text: Option<String>,
+    error: Option<String>,
+    warning: Option<String>,
+}
+
+impl CustomTheme {
+    pub fn base(&self) -> &str {
+        self.base.as_deref().unwrap_or(DARK)
+    }
+
+    pub fn apply(&self, mut theme: Theme) -> Result<Theme> {
+        override_color(&mut theme.border, &self.border)?;
+        override_border_type(&mut theme.border_type, &self.border_type)?;
+        override_color(&mut theme.highlight, &self.highlight)?;
+        if let Some(highlight_str) = &self.highlight_str {
+            theme.highlight_str = highlight_str.clone();
+        }
+        override_color(&mut theme.index, &self.index)?;
+        override_color(&mut theme.popup_border, &self.popup_border)?;
+        override_border_type(&mut theme.popup_border_type, &self.popup_border_type)?;
+        override_color(&mut theme.popup_text, &self.popup_text)?;
+        override_color(&mut theme.error, &self.error)?;
+        override_color(&mut theme.warning, &self.warning)?;
+        Ok(theme)
+    }
+}
+
+fn override_color(color: &mut Color, value: &Option<String>) -> Result<()> {
+    if let Some(value) = value {
+        *color = Color::from_str(value)
+            .ok()
+            .context(format!("Invalid color {value}"))?;
+    }
+    Ok(())
+}
+
+fn override_border_type(border_type: &mut BorderType, value: &Option<String>) -> Result<()> {
+    if let Some(value) = value {
+        *border_type = match value.to_lowercase().as_str() {
+            "plain" => BorderType::Plain,
+            "rounded" => BorderType::Rounded,
+            "double" => BorderType::Double,
+            "thick" => BorderType::Thick,
+            _ => bail!("Invalid border type {value}"),
+        };
+    }
+    Ok(())
+}
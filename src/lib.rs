@@ -1,17 +1,88 @@
 #![doc = include_str!("../README.md")]
 use backend::Note;
+use model::TodoFilter;
+use serde::Deserialize;
+use tuirealm::event::MouseEvent;
 
 mod backend;
 mod components;
+pub mod config;
 pub mod model;
 
+/// Key bindings flavour used by the list components
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum KeyMode {
+    #[default]
+    Standard,
+    Vim,
+}
+
+/// Order of the note list, ties are broken by creation date and then by file name
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// As arranged by the user, see the `index` file
+    #[default]
+    Manual,
+    /// Oldest first
+    Created,
+    /// Last saved first
+    Modified,
+    /// Alphabetical, ignoring case
+    Title,
+    /// Most open items first
+    OpenTodos,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Manual,
+        SortMode::Created,
+        SortMode::Modified,
+        SortMode::Title,
+        SortMode::OpenTodos,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Manual => SortMode::Created,
+            SortMode::Created => SortMode::Modified,
+            SortMode::Modified => SortMode::Title,
+            SortMode::Title => SortMode::OpenTodos,
+            SortMode::OpenTodos => SortMode::Manual,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::Created => "created",
+            SortMode::Modified => "modified",
+            SortMode::Title => "title",
+            SortMode::OpenTodos => "open items",
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 enum Id {
     PhantomListener,
+    MouseRouter,
     NoteList,
     TodoList,
-    InfoBox,
     EditPopup,
+    Help,
+    Stats,
+    History,
+    Versions,
+    Confirm,
+    Search,
+    Switcher,
+    Palette,
+    NotePicker,
+    Toast,
+    StatusBar,
+    Progress,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -20,8 +91,15 @@ enum Msg {
     None,
     NoteSelected(usize),
     TodoSelected(usize),
+    NoteClicked(usize),
+    TodoClicked(usize),
+    TodoStatusClicked(usize),
     EditNote,
     AddNote,
+    AddBlankNote,
+    AddNoteFromTemplate(String),
+    DuplicateNote,
+    SaveTemplate,
     RemoveNote,
     CloseEditNote(Option<String>),
     CloseEditTodo(Option<String>),
@@ -33,10 +111,77 @@ enum Msg {
     AddTodo,
     RemoveTodo,
     SwitchTodoStatus,
+    MoveNoteUp,
+    MoveNoteDown,
+    MoveTodoUp,
+    MoveTodoDown,
+    PickNoteForMove,
+    PickNoteForCopy,
+    MoveTodoTo(usize),
+    CopyTodoTo(usize),
+    PickNoteForMerge,
+    /// Merge the note at this index into the selected one
+    MergeNote(usize),
+    ToggleRangeAnchor,
+    SplitNote,
+    ToggleSelection,
+    ToggleArchived,
+    TogglePinned,
+    ToggleArchiveView,
+    ClearSelection,
+    CycleTodoFilter,
+    CycleSortMode,
+    /// Prompt for the value, the cycling actions go through every one in turn
+    PickTodoFilter,
+    SetTodoFilter(TodoFilter),
+    PickSortMode,
+    SetSortMode(SortMode),
+    /// Prompt for a theme, applied for the session
+    PickTheme,
+    SetTheme(String),
+    ToggleHideCompleted,
+    CutTodo,
+    CopyTodo,
+    PasteTodo,
+    RepeatLastAction,
+    OpenSearch,
+    OpenSwitcher,
+    OpenPalette,
+    SearchChanged(String),
+    SearchNext,
+    SearchPrevious,
+    Error(String),
+    Warning(String),
+    RetrySave,
+    Tick,
+    ShowHelp,
+    CloseHelp,
+    ShowStats,
+    CloseStats,
+    ShowHistory,
+    CloseHistory,
+    /// List the versions of the selected note, this one selected
+    ShowVersions(usize),
+    ShowVersionDiff(usize),
+    /// Ask before `RestoreVersion`, which replaces the content of the note
+    ConfirmRestoreVersion(usize),
+    RestoreVersion(usize),
+    CloseVersions,
+    /// Close the confirmation popup, sending the message of the answer
+    CloseConfirm(Box<Msg>),
+    /// Close the finder popup, sending the message of the picked candidate
+    CloseFinder(Option<Box<Msg>>),
+    /// Select the note and optionally one of its todos, the archive view is shown first when
+    /// the flag differs from the current view
+    JumpTo(bool, usize, Option<usize>),
+    /// Send the mouse event back to the lists, see `MouseRouter`
+    ForwardMouse(MouseEvent),
 }
 
 #[derive(PartialEq, Eq, Clone, PartialOrd)]
 enum AppEvent {
-    ErrorInitialized,
+    ErrorInitialized(String),
     NoteLoaded(Vec<Note>),
+    /// Mouse event replayed for the lists, whether focused or not
+    Mouse(MouseEvent),
 }
@@ -2,32 +2,71 @@ use std::{fs::create_dir, path::PathBuf};
 
 use clap::Parser;
 use home::home_dir;
-use todotui::model::Model;
+use todotui::{
+    KeyMode,
+    config::{Config, SymbolsMode},
+    model::Model,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[arg(short, long)]
     directory: Option<PathBuf>,
+    /// Enable vim style key bindings
+    #[arg(long)]
+    vim: bool,
+    /// Color theme: dark, light, high-contrast or one defined in config.toml
+    #[arg(short, long)]
+    theme: Option<String>,
+    /// Draw plain ASCII markers instead of emojis
+    #[arg(long)]
+    ascii: bool,
+    /// Commit every change to a git repository in the data directory
+    #[arg(long)]
+    git: bool,
 }
 
 fn main() {
     let args = Args::parse();
-    match args.directory {
-        Some(dir) => Model::new(dir).main_loop(),
+    let dir = match args.directory {
+        Some(dir) => dir,
         None => match home_dir() {
             Some(mut dir) => {
                 dir.push("todotui_data");
-                if dir.as_path().metadata().is_ok() {
-                    Model::new(dir).main_loop();
-                    return;
-                }
-                match create_dir(dir.clone()) {
-                    Ok(_) => Model::new(dir).main_loop(),
-                    Err(err) => println!("{}", err),
+                if dir.as_path().metadata().is_err() {
+                    if let Err(err) = create_dir(dir.clone()) {
+                        println!("{}", err);
+                        return;
+                    }
                 }
+                dir
             }
             None => panic!("Home directory discovery failed :("),
         },
     };
+
+    let mut config = match Config::load(&dir) {
+        Ok(config) => config,
+        Err(err) => {
+            println!("{:#}", err);
+            return;
+        }
+    };
+    if args.vim {
+        config.set_key_mode(KeyMode::Vim);
+    }
+    if args.ascii {
+        config.set_symbols_mode(SymbolsMode::Ascii);
+    }
+    if args.git {
+        config.set_git(true);
+    }
+    if let Some(theme) = args.theme {
+        if let Err(err) = config.set_theme(&theme) {
+            println!("{:#}", err);
+            return;
+        }
+    }
+    Model::new(dir, config).main_loop();
 }
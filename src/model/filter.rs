@@ -0,0 +1,92 @@
+use crate::backend::{Note, Todo};
+
+/// Status of the todos listed for the selected note
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TodoFilter {
+    #[default]
+    All,
+    Open,
+    Done,
+    Unknown,
+}
+
+impl TodoFilter {
+    pub const ALL: [TodoFilter; 4] = [
+        TodoFilter::All,
+        TodoFilter::Open,
+        TodoFilter::Done,
+        TodoFilter::Unknown,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            TodoFilter::All => TodoFilter::Open,
+            TodoFilter::Open => TodoFilter::Done,
+            TodoFilter::Done => TodoFilter::Unknown,
+            TodoFilter::Unknown => TodoFilter::All,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TodoFilter::All => "all",
+            TodoFilter::Open => "open",
+            TodoFilter::Done => "done",
+            TodoFilter::Unknown => "unknown",
+        }
+    }
+
+    pub fn matches(&self, done: Option<bool>) -> bool {
+        match self {
+            TodoFilter::All => true,
+            TodoFilter::Open => done == Some(false),
+            TodoFilter::Done => done == Some(true),
+            TodoFilter::Unknown => done.is_none(),
+        }
+    }
+}
+
+/// Todos of `note` passing the filter and its "hide completed" option, with their index in
+/// `note.todos()`. The list rows and the selected todo index refer to this vector
+pub fn visible_todos(note: &Note, filter: TodoFilter) -> Vec<(usize, Todo)> {
+    let hide_completed = note.hide_completed();
+    note.todos()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, todo)| {
+            let done = todo.done().unwrap_or_default();
+            filter.matches(done) && !(hide_completed && done == Some(true))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TodoFilter, visible_todos};
+    use crate::backend::Note;
+
+    #[test]
+    fn visible_todos_test() {
+        let mut note = Note::default();
+        for done in [Some(true), Some(false), None, Some(false)] {
+            assert!(note.create_todo().unwrap().set_done(done).is_ok());
+        }
+        let indices = |note: &Note, filter| -> Vec<usize> {
+            visible_todos(note, filter)
+                .into_iter()
+                .map(|(index, _)| index)
+                .collect()
+        };
+
+        assert_eq!(indices(&note, TodoFilter::All), vec![0, 1, 2, 3]);
+        assert_eq!(indices(&note, TodoFilter::Open), vec![1, 3]);
+        assert_eq!(indices(&note, TodoFilter::Done), vec![0]);
+        assert_eq!(indices(&note, TodoFilter::Unknown), vec![2]);
+
+        assert!(note.set_hide_completed(true).is_ok());
+        assert_eq!(indices(&note, TodoFilter::All), vec![1, 2, 3]);
+        assert_eq!(indices(&note, TodoFilter::Done), Vec::<usize>::new());
+
+        assert_eq!(TodoFilter::Unknown.next(), TodoFilter::All);
+    }
+}
@@ -0,0 +1,180 @@
+use tuirealm::ratatui::layout::{Constraint, Direction, Layout, Rect};
+use unicode_width::UnicodeWidthStr;
+
+/// Below this size nothing but a placeholder is drawn
+pub static MIN_WIDTH: u16 = 24;
+pub static MIN_HEIGHT: u16 = 8;
+/// Below this width the lists are stacked in a single column
+static STACKED_WIDTH: u16 = 70;
+/// Height of the progress gauge, shown when the todo list keeps at least as many rows
+static PROGRESS_HEIGHT: u16 = 3;
+
+/// Areas assigned to the main components
+#[derive(Debug, PartialEq, Eq)]
+pub struct MainLayout {
+    pub note_list: Rect,
+    /// Above the todo list, empty when the list would be too short
+    pub progress: Rect,
+    pub todo_list: Rect,
+    pub status_bar: Rect,
+}
+
+/// Size along one axis: a percentage of the screen, never below `min` nor above the screen
+#[derive(Debug, Clone, Copy)]
+pub struct Extent {
+    pub percent: u16,
+    pub min: u16,
+}
+
+impl Extent {
+    pub fn fixed(size: u16) -> Self {
+        Self {
+            percent: 0,
+            min: size,
+        }
+    }
+
+    pub fn relative(percent: u16, min: u16) -> Self {
+        Self { percent, min }
+    }
+
+    fn resolve(&self, available: u16) -> u16 {
+        let relative = (available as u32 * self.percent as u32 / 100) as u16;
+        relative.max(self.min).min(available)
+    }
+}
+
+pub fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+pub fn main_layout(area: Rect) -> Option<MainLayout> {
+    if too_small(area) {
+        return None;
+    }
+    let stacked = area.width < STACKED_WIDTH;
+    // The status bar takes the bottom margin
+    let status_bar = Rect {
+        y: area.bottom() - 1,
+        height: 1,
+        ..area
+    };
+    let lists = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width - 2,
+        height: area.height - 2,
+    };
+    let main_chunks = Layout::default()
+        .direction(match stacked {
+            true => Direction::Vertical,
+            false => Direction::Horizontal,
+        })
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(lists);
+
+    let todos = main_chunks[1];
+    let progress_height = match todos.height >= 2 * PROGRESS_HEIGHT {
+        true => PROGRESS_HEIGHT,
+        false => 0,
+    };
+    Some(MainLayout {
+        note_list: main_chunks[0],
+        progress: Rect {
+            height: progress_height,
+            ..todos
+        },
+        todo_list: Rect {
+            y: todos.y + progress_height,
+            height: todos.height - progress_height,
+            ..todos
+        },
+        status_bar,
+    })
+}
+
+/// Area centered inside `parent`, always contained in it
+pub fn centered(parent: Rect, width: Extent, height: Extent) -> Rect {
+    let width = width.resolve(parent.width);
+    let height = height.resolve(parent.height);
+    Rect {
+        x: parent.x + (parent.width - width) / 2,
+        y: parent.y + (parent.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Bottom right corner of `parent`, tall enough to show `text` wrapped inside the borders
+pub fn toast(parent: Rect, text: &str) -> Rect {
+    let width = Extent::relative(40, 30).resolve(parent.width);
+    let lines = (text.width() as u16).div_ceil(width.saturating_sub(2).max(1));
+    // Word wrapping may need an extra line
+    let lines = match lines > 1 {
+        true => lines + 1,
+        false => 1,
+    };
+    let height = (lines + 2).min(parent.height);
+    Rect {
+        x: parent.right() - width,
+        y: parent.bottom() - height,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tuirealm::ratatui::layout::Rect;
+
+    use super::{Extent, centered, main_layout, toast};
+
+    #[test]
+    fn layout_test() {
+        // Too small for anything
+        assert!(main_layout(Rect::new(0, 0, 10, 3)).is_none());
+
+        // Wide terminal: two columns
+        let layout = main_layout(Rect::new(0, 0, 120, 40)).unwrap();
+        assert_eq!(layout.note_list.y, layout.progress.y);
+        assert_eq!(layout.note_list.height, 38);
+        assert_eq!(layout.progress.bottom(), layout.todo_list.y);
+        assert_eq!(layout.todo_list.bottom(), layout.note_list.bottom());
+
+        // Narrow terminal: stacked lists
+        let layout = main_layout(Rect::new(0, 0, 40, 30)).unwrap();
+        assert_eq!(layout.note_list.x, layout.todo_list.x);
+        assert!(layout.note_list.y < layout.todo_list.y);
+        assert_eq!(layout.status_bar, Rect::new(0, 29, 40, 1));
+        assert_eq!(layout.todo_list.bottom(), 29);
+
+        // No room for the progress gauge
+        let layout = main_layout(Rect::new(0, 0, 40, 12)).unwrap();
+        assert_eq!(layout.progress.height, 0);
+    }
+
+    #[test]
+    fn popup_test() {
+        let parent = Rect::new(0, 0, 100, 40);
+        let popup = centered(parent, Extent::relative(50, 30), Extent::fixed(3));
+        assert_eq!(popup, Rect::new(25, 18, 50, 3));
+
+        // The minimum wins on small screens, the screen wins on tiny ones
+        let parent = Rect::new(0, 0, 40, 10);
+        let popup = centered(parent, Extent::relative(50, 30), Extent::fixed(3));
+        assert_eq!(popup, Rect::new(5, 3, 30, 3));
+        let parent = Rect::new(0, 0, 10, 2);
+        let popup = centered(parent, Extent::relative(50, 30), Extent::fixed(3));
+        assert_eq!(popup, parent);
+    }
+
+    #[test]
+    fn toast_test() {
+        let parent = Rect::new(0, 0, 100, 40);
+        assert_eq!(toast(parent, "Saved"), Rect::new(60, 37, 40, 3));
+        // 100 cells need three lines of 38, plus one for word wrapping
+        assert_eq!(toast(parent, &"x".repeat(100)), Rect::new(60, 34, 40, 6));
+        let parent = Rect::new(0, 0, 20, 4);
+        assert_eq!(toast(parent, &"x".repeat(100)), parent);
+    }
+}
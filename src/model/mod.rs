@@ -1,97 +1,213 @@
 use std::{
-    path::PathBuf,
-    sync::{Arc, RwLock},
-    time::Duration,
+    collections::VecDeque,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::{Duration, Instant},
 };
 
 use tuirealm::{
-    Application, AttrValue, Attribute, Event, EventListenerCfg, PollStrategy, Sub, SubClause,
-    SubEventClause, Update,
-    event::{Key, KeyEvent, KeyModifiers},
+    Application, AttrValue, Attribute, Component, Event, EventListenerCfg, PollStrategy, Sub,
+    SubClause, SubEventClause, Update,
+    event::{Key, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind},
     listener::{ListenerResult, Poll},
     props::{PropPayload, PropValue},
     ratatui::{
-        layout::{Constraint, Direction, Layout},
-        prelude::Rect,
-        widgets::Clear,
+        layout::Alignment,
+        layout::Rect,
+        widgets::{Clear, Paragraph, Wrap},
     },
-    terminal::{CrosstermTerminalAdapter, TerminalBridge},
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use chrono::Utc;
+use home::home_dir;
 
 use crate::{
-    AppEvent, Id, Msg,
-    backend::{NotesWall, NotesWallBuilder},
-    components::{EditPopup, EditPopupType, NoteList, PhantomListener, ShortcutsLegend, TodoList},
+    AppEvent, Id, KeyMode, Msg, SortMode,
+    backend::{Note, NotesWall, NotesWallBuilder, Todo},
+    components::{
+        Candidate, Confirm, Context, EditPopup, EditPopupType, Finder, Help, History, Matching,
+        MouseRouter, NoteList, PhantomListener, Progress, ProgressGauge, Severity, StatsData,
+        StatsView, Status, StatusBar, Toast, TodoList, Versions, commands, substring_indices,
+    },
+    config::{Config, Theme},
 };
 
+mod filter;
+mod layout;
+mod stats;
+mod terminal;
+
+use stats::Stats;
+
+pub(crate) use filter::TodoFilter;
+use layout::Extent;
+use terminal::TerminalGuard;
+
 type SharedWall = Arc<RwLock<NotesWall>>;
+type MouseQueue = Arc<Mutex<VecDeque<MouseEvent>>>;
+
+static ERROR_TIMEOUT: Duration = Duration::from_secs(8);
+static WARNING_TIMEOUT: Duration = Duration::from_secs(4);
+static MESSAGE_TIMEOUT: Duration = Duration::from_secs(3);
+static POPUPS: &[Id] = &[
+    Id::EditPopup,
+    Id::Help,
+    Id::Stats,
+    Id::History,
+    Id::Versions,
+    Id::Confirm,
+    Id::Search,
+    Id::Switcher,
+    Id::Palette,
+    Id::NotePicker,
+];
 
 pub struct Model {
     quit: bool,   // Becomes true when the user presses <ESC>
     redraw: bool, // Tells whether to refresh the UI; performance optimization
-    text_edit_popup_open: bool,
+    /// Popup owning the focus, if any
+    popup: Option<Id>,
     selected_note_index: usize,
+    /// Index among the todos passing the filter
     selected_todo_index: usize,
+    todo_filter: TodoFilter,
+    /// Note and visible index where the range of todos started, up to the selected one
+    range_anchor: Option<(Note, usize)>,
+    /// Todos marked for the bulk actions, kept by identity so that reloads and filters keep them
+    selection: Vec<Todo>,
+    clipboard: Option<Todo>,
+    last_action: Option<Msg>,
+    /// Matches are highlighted in both lists
+    search_query: Option<String>,
+    /// Messages raised while handling another one, processed once its chain is over
+    deferred: VecDeque<Msg>,
+    /// Text of the visible toast and when it goes away
+    toast: Option<(String, Instant)>,
+    /// Feedback shown in the status bar and when it goes away
+    status_message: Option<(String, Instant)>,
+    data_dir: String,
+    key_mode: KeyMode,
+    theme: Theme,
+    /// Themes offered by the theme prompt, by name
+    themes: Vec<(String, Theme)>,
     notes_wall: SharedWall,
-    terminal: TerminalBridge<CrosstermTerminalAdapter>,
+    /// Mouse events on their way back to the lists, see `MouseRouter`
+    mouse_events: MouseQueue,
+    terminal: TerminalGuard,
     app: Application<Id, Msg, AppEvent>,
 }
 
 impl Model {
-    pub fn new(path: PathBuf) -> Self {
+    pub fn new(path: PathBuf, config: Config) -> Self {
         let quit = false;
         let redraw = true;
-        let text_edit_popup_open = false;
+        let popup = None;
         let selected_note_index = 0;
         let selected_todo_index = 0;
+        let todo_filter = TodoFilter::default();
+        let clipboard = None;
+        let last_action = None;
+        let search_query = None;
+        let deferred = VecDeque::new();
+        let toast = None;
+        let status_message = None;
+        let data_dir = display_path(&path);
+        let key_mode = config.key_mode();
+        let theme = config.theme().unwrap_or_default();
+        let themes = config
+            .theme_names()
+            .into_iter()
+            .filter_map(|name| {
+                let theme = config.named_theme(&name).ok()?;
+                Some((name, theme))
+            })
+            .collect();
+        terminal::install_panic_hook(path.clone());
         let notes_wall = Arc::new(RwLock::new(
             NotesWallBuilder::default()
                 .folder_path(path)
+                .sort(config.sort_mode())
+                .archive_after(config.archive_after())
+                .git(config.git())
                 .build()
                 .unwrap(),
         ));
-        let mut terminal = TerminalBridge::init_crossterm().expect("Cannot create terminal bridge");
-        let _ = terminal.enable_raw_mode();
-        let _ = terminal.enter_alternate_screen();
+        let mouse_events = MouseQueue::default();
+        let terminal = TerminalGuard::init().expect("Cannot create terminal bridge");
         let mut app: Application<Id, Msg, AppEvent> = Application::init(
             EventListenerCfg::default()
                 .crossterm_input_listener(Duration::from_millis(10), 3)
+                .tick_interval(Duration::from_secs(1))
                 .add_port(
                     Box::new(NotesProvider::new(notes_wall.clone())),
                     Duration::from_millis(100),
                     3,
+                )
+                .add_port(
+                    Box::new(MouseReplay(mouse_events.clone())),
+                    Duration::from_millis(10),
+                    3,
                 ),
         );
         assert!(
-            app.mount(Id::NoteList, Box::<NoteList>::default(), vec![])
-                .is_ok()
+            app.mount(
+                Id::NoteList,
+                Box::new(NoteList::new(key_mode, &theme)),
+                vec![Self::mouse_subscription()]
+            )
+            .is_ok()
         );
         assert!(
-            app.mount(Id::InfoBox, Box::<ShortcutsLegend>::default(), vec![])
-                .is_ok()
+            app.mount(
+                Id::TodoList,
+                Box::new(TodoList::new(key_mode, &theme)),
+                vec![Self::mouse_subscription()]
+            )
+            .is_ok()
         );
         assert!(
-            app.mount(Id::TodoList, Box::<TodoList>::default(), vec![])
-                .is_ok()
+            app.mount(
+                Id::StatusBar,
+                Box::new(StatusBar::new(&Status::default(), &theme)),
+                vec![]
+            )
+            .is_ok()
+        );
+        assert!(
+            app.mount(
+                Id::Progress,
+                Box::new(ProgressGauge::new(&Progress::default(), &theme)),
+                vec![]
+            )
+            .is_ok()
+        );
+        assert!(
+            app.mount(
+                Id::MouseRouter,
+                Box::<MouseRouter>::default(),
+                vec![Self::unless_popup(SubEventClause::Any)]
+            )
+            .is_ok()
         );
         assert!(
             app.mount(
                 Id::PhantomListener,
                 Box::<PhantomListener>::default(),
                 vec![
+                    // Esc on a popup only closes it
+                    Self::unless_popup(SubEventClause::Keyboard(KeyEvent {
+                        code: Key::Esc,
+                        modifiers: KeyModifiers::NONE
+                    })),
                     Sub::new(
-                        SubEventClause::Keyboard(KeyEvent {
-                            code: Key::Esc,
-                            modifiers: KeyModifiers::NONE
-                        }),
+                        SubEventClause::Discriminant(AppEvent::ErrorInitialized(String::new())),
                         SubClause::Always
                     ),
-                    Sub::new(
-                        SubEventClause::User(AppEvent::ErrorInitialized),
-                        SubClause::Always
-                    )
+                    Sub::new(SubEventClause::Tick, SubClause::Always),
+                    // Any message triggers a redraw with the new layout
+                    Sub::new(SubEventClause::WindowResize, SubClause::Always)
                 ]
             )
             .is_ok()
@@ -103,12 +219,26 @@ impl Model {
         Self {
             quit,
             redraw,
-            text_edit_popup_open,
+            popup,
             selected_note_index,
             selected_todo_index,
+            todo_filter,
+            range_anchor: None,
+            selection: Vec::new(),
+            clipboard,
+            last_action,
+            search_query,
+            deferred,
+            toast,
+            status_message,
+            data_dir,
+            key_mode,
+            theme,
+            themes,
             terminal,
             app,
             notes_wall,
+            mouse_events,
         }
     }
 
@@ -119,80 +249,137 @@ impl Model {
                 messages.iter().map(Some).for_each(|msg| {
                     let mut msg = msg.cloned();
                     while msg.is_some() {
-                        msg = self.update(msg);
+                        msg = self.update(msg).or_else(|| self.deferred.pop_front());
                     }
                 });
             }
 
             // Redraw
             if self.redraw {
+                self.refresh_status_bar();
                 self.view();
                 self.redraw = false;
             }
         }
-        let _ = self.terminal.leave_alternate_screen();
-        let _ = self.terminal.disable_raw_mode();
+        self.wall().wait_for_commits();
+        self.terminal.restore();
         let _ = self.terminal.clear_screen();
     }
 
+    /// Mouse events reach the lists even when they are not focused, unless a popup is open.
+    /// Mouse clauses can't be built outside tuirealm so the lists get the events replayed by the
+    /// model as `AppEvent::Mouse`
+    fn mouse_subscription() -> Sub<Id, AppEvent> {
+        Self::unless_popup(SubEventClause::Discriminant(AppEvent::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved,
+            modifiers: KeyModifiers::NONE,
+            column: 0,
+            row: 0,
+        })))
+    }
+
+    fn unless_popup(event: SubEventClause<AppEvent>) -> Sub<Id, AppEvent> {
+        Sub::new(
+            event,
+            SubClause::Not(Box::new(SubClause::OrMany(
+                POPUPS.iter().cloned().map(SubClause::IsMounted).collect(),
+            ))),
+        )
+    }
+
+    fn open_popup(&mut self, id: Id, component: Box<dyn Component<Msg, AppEvent>>) {
+        assert!(self.app.remount(id.clone(), component, vec![]).is_ok());
+        assert!(self.app.active(&id).is_ok());
+        self.popup = Some(id);
+    }
+
+    /// The focus goes back to the component owning it before the popup
+    fn close_popup(&mut self) {
+        if let Some(id) = self.popup.take() {
+            assert!(self.app.umount(&id).is_ok());
+        }
+    }
+
     fn view(&mut self) {
         let _ = self.terminal.raw_mut().draw(|f| {
-            // Prepare chunks
-            let main_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .margin(1)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .split(f.area());
-
-            let sub_chunk = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Percentage(85), Constraint::Percentage(15)])
-                .split(main_chunks[0]);
-
-            self.app.view(&Id::NoteList, f, sub_chunk[0]);
-            self.app.view(&Id::InfoBox, f, sub_chunk[1]);
-            self.app.view(&Id::TodoList, f, main_chunks[1]);
-
-            if self.text_edit_popup_open {
-                let popup = Self::draw_area_in_absolute(f.area(), 30, 3);
-                f.render_widget(Clear, popup);
-                self.app.view(&Id::EditPopup, f, popup);
+            let Some(layout) = layout::main_layout(f.area()) else {
+                let message = format!(
+                    "Terminal too small\nat least {}x{} is needed",
+                    layout::MIN_WIDTH,
+                    layout::MIN_HEIGHT
+                );
+                f.render_widget(
+                    Paragraph::new(message)
+                        .alignment(Alignment::Center)
+                        .wrap(Wrap { trim: true }),
+                    f.area(),
+                );
+                return;
+            };
+
+            self.app.view(&Id::NoteList, f, layout.note_list);
+            if layout.progress.height > 0 {
+                self.app.view(&Id::Progress, f, layout.progress);
             }
-        });
-    }
+            self.app.view(&Id::TodoList, f, layout.todo_list);
+            self.app.view(&Id::StatusBar, f, layout.status_bar);
 
-    fn draw_area_in_absolute(parent: Rect, width: u16, height: u16) -> Rect {
-        let new_area = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Length((parent.height - height) / 2),
-                    Constraint::Length(height),
-                    Constraint::Length((parent.height - height) / 2),
-                ]
-                .as_ref(),
-            )
-            .split(parent);
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Length((parent.width - width) / 2),
-                    Constraint::Length(width),
-                    Constraint::Length((parent.width - width) / 2),
-                ]
-                .as_ref(),
-            )
-            .split(new_area[1])[1]
+            if let Some(id) = &self.popup {
+                let area = match id {
+                    Id::EditPopup => {
+                        layout::centered(f.area(), Extent::relative(50, 30), Extent::fixed(3))
+                    }
+                    Id::Confirm => {
+                        layout::centered(f.area(), Extent::relative(50, 30), Extent::fixed(5))
+                    }
+                    _ => layout::centered(
+                        f.area(),
+                        Extent::relative(60, 40),
+                        Extent::relative(80, 10),
+                    ),
+                };
+                f.render_widget(Clear, area);
+                self.app.view(id, f, area);
+            }
+
+            if let Some((text, _)) = &self.toast {
+                let above_status_bar = Rect {
+                    height: layout.status_bar.y - f.area().y,
+                    ..f.area()
+                };
+                let toast = layout::toast(above_status_bar, text);
+                f.render_widget(Clear, toast);
+                self.app.view(&Id::Toast, f, toast);
+            }
+        });
     }
 }
 
 impl Update<Msg> for Model {
     fn update(&mut self, msg: Option<Msg>) -> Option<Msg> {
-        self.redraw = true;
-        match msg.unwrap_or(Msg::None) {
+        let msg = msg.unwrap_or(Msg::None);
+        // Ticks redraw only when a toast expires, forwarded mouse events once the lists handle them
+        self.redraw |= !matches!(msg, Msg::Tick | Msg::ForwardMouse(_));
+        if Self::is_repeatable(&msg) {
+            self.last_action = Some(msg.clone());
+        }
+        let selected = (
+            self.selected_note_index,
+            self.sorted().then(|| self.selected_note()),
+        );
+        let next = self.handle(msg);
+        if let (index, Some(Some(note))) = selected {
+            self.follow_note(index, &note);
+        }
+        next
+    }
+}
+
+impl Model {
+    fn handle(&mut self, msg: Msg) -> Option<Msg> {
+        match msg {
             Msg::AppClose => {
-                if !self.text_edit_popup_open {
+                if self.popup.is_none() {
                     self.quit = true;
                 }
                 None
@@ -206,7 +393,22 @@ impl Update<Msg> for Model {
             }
             Msg::TodoSelected(index) => {
                 self.selected_todo_index = index;
-                None
+                self.refresh_marked_range()
+            }
+            Msg::NoteClicked(index) => {
+                assert!(self.app.active(&Id::NoteList).is_ok());
+                self.selected_note_index = index;
+                self.reload_todo_list()
+            }
+            Msg::TodoClicked(index) => {
+                assert!(self.app.active(&Id::TodoList).is_ok());
+                self.selected_todo_index = index;
+                self.refresh_marked_range()
+            }
+            Msg::TodoStatusClicked(index) => {
+                assert!(self.app.active(&Id::TodoList).is_ok());
+                self.selected_todo_index = index;
+                Some(Msg::SwitchTodoStatus)
             }
             Msg::NoteListBlur => {
                 assert!(self.app.active(&Id::TodoList).is_ok());
@@ -217,7 +419,11 @@ impl Update<Msg> for Model {
                 None
             }
             Msg::EditNote => self.prepare_note_edit_popup(),
-            Msg::AddNote => self.add_note(),
+            Msg::AddNote => self.new_note(),
+            Msg::AddBlankNote => self.add_note(),
+            Msg::AddNoteFromTemplate(name) => self.add_note_from_template(&name),
+            Msg::DuplicateNote => self.duplicate_note(),
+            Msg::SaveTemplate => self.save_template(),
             Msg::RemoveNote => self.remove_note(),
             Msg::ReloadNoteList => self.reload_note_list(),
             Msg::ReloadTodoList => self.reload_todo_list(),
@@ -225,63 +431,1262 @@ impl Update<Msg> for Model {
             Msg::AddTodo => self.add_todo(),
             Msg::RemoveTodo => self.remove_todo(),
             Msg::SwitchTodoStatus => self.switch_todo_status(),
+            Msg::MoveNoteUp => self.move_note(false),
+            Msg::MoveNoteDown => self.move_note(true),
+            Msg::MoveTodoUp => self.move_todo(false),
+            Msg::MoveTodoDown => self.move_todo(true),
+            Msg::PickNoteForMove => self.open_note_picker("Move to note", Msg::MoveTodoTo),
+            Msg::PickNoteForCopy => self.open_note_picker("Copy to note", Msg::CopyTodoTo),
+            Msg::MoveTodoTo(note) => self.transfer_todo(note, false),
+            Msg::CopyTodoTo(note) => self.transfer_todo(note, true),
+            Msg::PickNoteForMerge => self.open_note_picker("Merge into this note", Msg::MergeNote),
+            Msg::MergeNote(note) => self.merge_note(note),
+            Msg::ToggleRangeAnchor => self.toggle_range_anchor(),
+            Msg::SplitNote => self.split_note(),
+            Msg::ToggleSelection => self.toggle_selection(),
+            Msg::ToggleArchived => self.toggle_archived(),
+            Msg::TogglePinned => self.toggle_pinned(),
+            Msg::ToggleArchiveView => self.toggle_archive_view(),
+            Msg::ClearSelection => {
+                self.selection.clear();
+                self.refresh_todo_table();
+                None
+            }
+            Msg::CycleTodoFilter => self.set_todo_filter(self.todo_filter.next()),
+            Msg::CycleSortMode => {
+                let sort = self.wall().sort().next();
+                self.set_sort_mode(sort)
+            }
+            Msg::PickTodoFilter => self.pick_todo_filter(),
+            Msg::SetTodoFilter(filter) => self.set_todo_filter(filter),
+            Msg::PickSortMode => self.pick_sort_mode(),
+            Msg::SetSortMode(sort) => self.set_sort_mode(sort),
+            Msg::PickTheme => self.pick_theme(),
+            Msg::SetTheme(name) => self.set_theme(&name),
+            Msg::ToggleHideCompleted => self.toggle_hide_completed(),
+            Msg::CutTodo => self.cut_todo(),
+            Msg::CopyTodo => self.copy_todo(),
+            Msg::PasteTodo => self.paste_todo(),
+            Msg::RepeatLastAction => self.last_action.clone(),
+            Msg::OpenSearch => self.open_search(),
+            Msg::OpenSwitcher => self.open_switcher(),
+            Msg::OpenPalette => self.open_palette(),
+            Msg::SearchChanged(query) => self.search_changed(query),
+            Msg::SearchNext => self.search_step(true),
+            Msg::SearchPrevious => self.search_step(false),
+            Msg::Error(text) => self.show_toast(Severity::Error, text),
+            Msg::Warning(text) => self.show_toast(Severity::Warning, text),
+            Msg::RetrySave => self.retry_save(),
+            Msg::Tick => self.expire_notifications(),
+            Msg::ShowHelp => self.show_help(),
+            Msg::CloseHelp | Msg::CloseStats | Msg::CloseHistory | Msg::CloseVersions => {
+                self.close_popup();
+                None
+            }
+            Msg::ShowStats => self.show_stats(),
+            Msg::ShowHistory => self.show_history(),
+            Msg::ShowVersions(index) => self.show_versions(index),
+            Msg::ShowVersionDiff(index) => self.show_version_diff(index),
+            Msg::ConfirmRestoreVersion(index) => self.confirm_restore_version(index),
+            Msg::RestoreVersion(index) => self.restore_version(index),
+            Msg::CloseConfirm(answer) => {
+                self.close_popup();
+                Some(*answer)
+            }
+            Msg::CloseFinder(target) => self.close_finder(target),
+            Msg::JumpTo(archive, note, todo) => self.jump_to(archive, note, todo),
+            Msg::ForwardMouse(ev) => {
+                self.mouse_events
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push_back(ev);
+                None
+            }
         }
     }
-}
 
-impl Model {
+    /// Whether the note list is sorted by something changing with the notes
+    fn sorted(&self) -> bool {
+        self.wall().sort() != SortMode::Manual
+    }
+
+    /// Keep `note` selected when a change moved it in the sorted list, unless the selection
+    /// changed on purpose
+    fn follow_note(&mut self, index: usize, note: &Note) {
+        if self.selected_note_index != index {
+            return;
+        }
+        let position = self
+            .wall()
+            .get_notes()
+            .iter()
+            .position(|other| other == note);
+        if let Some(position) = position.filter(|position| *position != index) {
+            self.selected_note_index = position;
+            self.refresh_note_table();
+            self.select_note_row();
+        }
+    }
+
+    fn is_repeatable(msg: &Msg) -> bool {
+        matches!(
+            msg,
+            Msg::RemoveNote
+                | Msg::RemoveTodo
+                | Msg::SwitchTodoStatus
+                | Msg::CutTodo
+                | Msg::PasteTodo
+                | Msg::MoveTodoUp
+                | Msg::MoveTodoDown
+        )
+    }
+
+    /// A panic while holding the lock doesn't make the notes unusable, keep going with them
+    fn wall(&self) -> RwLockReadGuard<'_, NotesWall> {
+        self.notes_wall
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wall_mut(&self) -> RwLockWriteGuard<'_, NotesWall> {
+        self.notes_wall
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn selected_note(&self) -> Option<Note> {
+        self.wall()
+            .get_notes()
+            .into_iter()
+            .nth(self.selected_note_index)
+    }
+
+    fn visible_todos(&self, note: &Note) -> Vec<(usize, Todo)> {
+        filter::visible_todos(note, self.todo_filter)
+    }
+
+    /// The selected todo with its index in `note.todos()`
+    fn selected_todo(&self, note: &Note) -> Option<(usize, Todo)> {
+        self.visible_todos(note)
+            .into_iter()
+            .nth(self.selected_todo_index)
+    }
+
+    /// Queue the error, if any, to be shown once the current message has been handled
+    fn report(&mut self, result: Result<()>) -> bool {
+        match result {
+            Ok(_) => true,
+            Err(err) => {
+                self.deferred.push_back(Msg::Error(format!("{:#}", err)));
+                false
+            }
+        }
+    }
+
+    /// Save the note, on failure it stays dirty and can be saved again with `Msg::RetrySave`
+    fn save_note(&mut self, note: &Note) -> bool {
+        let title = note.title().unwrap_or_default();
+        let result = note
+            .save()
+            .with_context(|| format!("Cannot save \"{}\", press r to retry", title));
+        let saved = self.report(result);
+        if saved {
+            self.notify(format!("Saved \"{}\"", title));
+        }
+        // The dirty marker and the progress gauge follow the note
+        self.refresh_note_table();
+        saved
+    }
+
+    fn retry_save(&mut self) -> Option<Msg> {
+        let dirty: Vec<Note> = self
+            .wall()
+            .all_notes()
+            .into_iter()
+            .filter(|note| note.dirty())
+            .collect();
+        if dirty.is_empty() {
+            return Some(Msg::Warning("Every note is already saved".to_string()));
+        }
+        if dirty.iter().all(|note| self.save_note(note)) {
+            self.refresh_note_table();
+            self.hide_toast();
+            self.notify(format!("Saved {} notes", dirty.len()));
+        }
+        None
+    }
+
+    fn show_toast(&mut self, severity: Severity, text: String) -> Option<Msg> {
+        let timeout = match severity {
+            Severity::Warning => WARNING_TIMEOUT,
+            Severity::Error => ERROR_TIMEOUT,
+        };
+        assert!(
+            self.app
+                .remount(
+                    Id::Toast,
+                    Box::new(Toast::new(severity, &text, &self.theme)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        self.toast = Some((text, Instant::now() + timeout));
+        None
+    }
+
+    fn hide_toast(&mut self) {
+        if self.toast.take().is_some() {
+            assert!(self.app.umount(&Id::Toast).is_ok());
+        }
+    }
+
+    fn expire_notifications(&mut self) -> Option<Msg> {
+        let now = Instant::now();
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|(_, expiry)| *expiry <= now)
+        {
+            self.hide_toast();
+            self.redraw = true;
+        }
+        if self
+            .status_message
+            .as_ref()
+            .is_some_and(|(_, expiry)| *expiry <= now)
+        {
+            self.status_message = None;
+            self.redraw = true;
+        }
+        None
+    }
+
+    /// Show a short feedback about the last action in the status bar
+    fn notify(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now() + MESSAGE_TIMEOUT));
+    }
+
+    /// The status bar and the progress gauge describe the selected note
+    fn refresh_status_bar(&mut self) {
+        let notes = self.wall().get_notes();
+        let note = notes.get(self.selected_note_index);
+        let progress = note.map(Progress::of).unwrap_or_default();
+        let status = Status {
+            data_dir: self.data_dir.clone(),
+            notes: notes.len(),
+            open: progress.open,
+            done: progress.done,
+            unknown: progress.unknown,
+            selected: note.map_or(0, |note| self.marked_todos(note).len()),
+            sort: Some(self.wall().sort())
+                .filter(|sort| *sort != SortMode::Manual)
+                .map(|sort| sort.label().to_string()),
+            filter: Self::filter_label(
+                self.todo_filter,
+                note.is_some_and(|note| note.hide_completed()),
+            ),
+            message: self
+                .status_message
+                .as_ref()
+                .map(|(message, _)| message.clone()),
+        };
+        assert!(
+            self.app
+                .remount(
+                    Id::StatusBar,
+                    Box::new(StatusBar::new(&status, &self.theme)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(
+            self.app
+                .remount(
+                    Id::Progress,
+                    Box::new(ProgressGauge::new(&progress, &self.theme)),
+                    vec![]
+                )
+                .is_ok()
+        );
+    }
+
+    fn filter_label(filter: TodoFilter, hide_completed: bool) -> Option<String> {
+        match (filter, hide_completed) {
+            (TodoFilter::All, false) => None,
+            (TodoFilter::All, true) => Some("done hidden".to_string()),
+            (filter, false) => Some(format!("{} only", filter.label())),
+            (filter, true) => Some(format!("{} only, done hidden", filter.label())),
+        }
+    }
+
+    fn cut_todo(&mut self) -> Option<Msg> {
+        if let Some(mut note) = self.selected_note() {
+            if let Some((_, todo)) = self.selected_todo(&note) {
+                self.clipboard = todo.duplicate().ok();
+                if self.report(note.remove_todo(&todo)) && self.save_note(&note) {
+                    self.notify("Cut item");
+                }
+            }
+        }
+        Some(Msg::ReloadTodoList)
+    }
+
+    fn copy_todo(&mut self) -> Option<Msg> {
+        if let Some(note) = self.selected_note() {
+            if let Some((_, todo)) = self.selected_todo(&note) {
+                self.clipboard = todo.duplicate().ok();
+                self.notify("Copied item");
+            }
+        }
+        None
+    }
+
+    fn paste_todo(&mut self) -> Option<Msg> {
+        let todo = self.clipboard.as_ref()?.duplicate().ok()?;
+        if let Some(mut note) = self.selected_note() {
+            let index = self
+                .selected_todo(&note)
+                .map_or(0, |(selected, _)| selected + 1);
+            if self.report(note.insert_todo(index, todo)) {
+                if self.save_note(&note) {
+                    self.notify("Pasted item");
+                }
+                // The pasted todo may not pass the filter, the selection is clamped then
+                self.selected_todo_index = self
+                    .visible_todos(&note)
+                    .iter()
+                    .position(|(visible, _)| *visible == index)
+                    .unwrap_or(self.selected_todo_index + 1);
+            }
+        }
+        Some(Msg::ReloadTodoList)
+    }
+
+    fn show_help(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        self.open_popup(
+            Id::Help,
+            Box::new(Help::new(self.focus_context(), self.key_mode, &self.theme)),
+        );
+        None
+    }
+
+    fn show_stats(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        let stats = Stats::compute(&self.wall().all_notes(), Utc::now());
+        let data = StatsData {
+            completions: &stats.completions,
+            notes: &stats.notes,
+            average_completion: stats.average_completion,
+            longest_open: &stats.longest_open,
+        };
+        self.open_popup(Id::Stats, Box::new(StatsView::new(&data, &self.theme)));
+        None
+    }
+
+    fn show_history(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        let note = self.selected_note()?;
+        let title = note.title().unwrap_or_default();
+        match note.history() {
+            Ok(entries) => {
+                self.open_popup(
+                    Id::History,
+                    Box::new(History::new(&title, &entries, &self.theme)),
+                );
+                None
+            }
+            Err(err) => Some(Msg::Error(format!("{:#}", err))),
+        }
+    }
+
+    /// Open the versions of the selected note, or go back to them from the changes of one
+    fn show_versions(&mut self, selected: usize) -> Option<Msg> {
+        if self.popup.is_some() && self.popup != Some(Id::Versions) {
+            return None;
+        }
+        let note = self.selected_note()?;
+        let title = note.title().unwrap_or_default();
+        match note.versions() {
+            Ok(versions) => {
+                self.open_popup(
+                    Id::Versions,
+                    Box::new(Versions::new(&title, &versions, selected, &self.theme)),
+                );
+                None
+            }
+            Err(err) => Some(Msg::Error(format!("{:#}", err))),
+        }
+    }
+
+    fn show_version_diff(&mut self, index: usize) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let result = note.versions().and_then(|versions| {
+            let version = versions.get(index).cloned();
+            let version = version.context("No such version")?;
+            Ok((note.version_diff(&version)?, version))
+        });
+        match result {
+            Ok((diff, version)) => {
+                self.open_popup(
+                    Id::Versions,
+                    Box::new(Versions::diff(&version, index, &diff, &self.theme)),
+                );
+                None
+            }
+            Err(err) => Some(Msg::Error(format!("{:#}", err))),
+        }
+    }
+
+    /// Answering no goes back to the versions
+    fn confirm_restore_version(&mut self, index: usize) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let version = note
+            .versions()
+            .and_then(|versions| versions.get(index).cloned().context("No such version"));
+        let version = match version {
+            Ok(version) => version,
+            Err(err) => return Some(Msg::Error(format!("{:#}", err))),
+        };
+        let question = format!(
+            "Restore the items as of {}? The current ones stay in the history",
+            version.time
+        );
+        self.close_popup();
+        self.open_popup(
+            Id::Confirm,
+            Box::new(Confirm::new(
+                &question,
+                Msg::RestoreVersion(index),
+                Msg::ShowVersions(index),
+                &self.theme,
+            )),
+        );
+        None
+    }
+
+    fn restore_version(&mut self, index: usize) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let title = note.title().unwrap_or_default();
+        let version = note
+            .versions()
+            .and_then(|versions| versions.get(index).cloned().context("No such version"));
+        let result = version.and_then(|version| note.restore(&version).map(|_| version));
+        self.close_popup();
+        match result.with_context(|| format!("Cannot restore \"{}\"", title)) {
+            Ok(version) => {
+                self.notify(format!(
+                    "Restored \"{}\" as of {}",
+                    note.title().unwrap_or_default(),
+                    version.time
+                ));
+                // The restored todos replace the marked ones
+                self.selection.clear();
+                self.range_anchor = None;
+                Some(Msg::ReloadNoteList)
+            }
+            Err(err) => Some(Msg::Error(format!("{:#}", err))),
+        }
+    }
+
+    fn focus_context(&self) -> Context {
+        match self.app.focus() {
+            Some(Id::TodoList) => Context::TodoList,
+            _ => Context::NoteList,
+        }
+    }
+
+    fn open_search(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        let mut candidates = Vec::new();
+        for (archive, note_index, note) in self.searched_notes() {
+            let title = note.title().unwrap_or_default();
+            candidates.push(Candidate::new(
+                &title,
+                Msg::JumpTo(archive, note_index, None),
+            ));
+            for (todo_index, todo) in filter::visible_todos(&note, TodoFilter::All) {
+                candidates.push(
+                    Candidate::new(
+                        todo.description().unwrap_or_default(),
+                        Msg::JumpTo(archive, note_index, Some(todo_index)),
+                    )
+                    .with_context(&title),
+                );
+            }
+        }
+        self.open_popup(
+            Id::Search,
+            Box::new(
+                Finder::new(
+                    "Search",
+                    self.search_query.as_deref().unwrap_or_default(),
+                    candidates,
+                    Matching::Substring,
+                    &self.theme,
+                )
+                .on_change(Msg::SearchChanged),
+            ),
+        );
+        None
+    }
+
+    /// Notes of the shown view then the ones of the other view, so that archived notes stay
+    /// searchable, with whether they are archived and their index in their view
+    fn searched_notes(&self) -> Vec<(bool, usize, Note)> {
+        let archive = self.wall().archive();
+        [archive, !archive]
+            .into_iter()
+            .flat_map(|archive| {
+                self.wall()
+                    .notes_in(archive)
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(index, note)| (archive, index, note))
+            })
+            .collect()
+    }
+
+    fn open_switcher(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        let archive = self.wall().archive();
+        let candidates = self
+            .wall()
+            .get_notes()
+            .iter()
+            .enumerate()
+            .map(|(index, note)| {
+                Candidate::new(
+                    note.title().unwrap_or_default(),
+                    Msg::JumpTo(archive, index, None),
+                )
+            })
+            .collect();
+        self.open_popup(
+            Id::Switcher,
+            Box::new(Finder::new(
+                "Go to note",
+                "",
+                candidates,
+                Matching::Fuzzy,
+                &self.theme,
+            )),
+        );
+        None
+    }
+
+    fn open_palette(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        self.open_popup(
+            Id::Palette,
+            Box::new(Finder::new(
+                "Command",
+                "",
+                commands(self.focus_context()),
+                Matching::Fuzzy,
+                &self.theme,
+            )),
+        );
+        None
+    }
+
+    /// Highlight the matches in both lists while typing
+    fn search_changed(&mut self, query: String) -> Option<Msg> {
+        self.search_query = Some(query).filter(|query| !query.is_empty());
+        self.refresh_note_table();
+        self.refresh_todo_table();
+        None
+    }
+
+    /// Cancelling a search clears the highlighted matches
+    fn close_finder(&mut self, target: Option<Box<Msg>>) -> Option<Msg> {
+        if self.popup == Some(Id::Search) && target.is_none() {
+            self.search_changed(String::new());
+        }
+        self.close_popup();
+        target.map(|target| *target)
+    }
+
+    fn jump_to(&mut self, archive: bool, note: usize, todo: Option<usize>) -> Option<Msg> {
+        if archive != self.wall().archive() {
+            self.toggle_archive_view();
+        }
+        self.selected_note_index = note;
+        self.reload_note_list();
+        self.selected_todo_index = todo
+            .and_then(|todo| self.visible_position(todo))
+            .unwrap_or_default();
+        self.reload_todo_list();
+        let focus = match todo {
+            Some(_) => Id::TodoList,
+            None => Id::NoteList,
+        };
+        assert!(self.app.active(&focus).is_ok());
+        None
+    }
+
+    /// Position among the visible todos of the selected note, the status filter is reset
+    /// when it hides the todo
+    fn visible_position(&mut self, todo: usize) -> Option<usize> {
+        let note = self.selected_note()?;
+        let position = |filter| {
+            filter::visible_todos(&note, filter)
+                .iter()
+                .position(|(index, _)| *index == todo)
+        };
+        position(self.todo_filter).or_else(|| {
+            self.todo_filter = TodoFilter::All;
+            position(TodoFilter::All)
+        })
+    }
+
+    /// Go to the next match across every note, titles first and then their items. The notes of
+    /// the other view, archived or not, come after the shown ones
+    fn search_step(&mut self, forward: bool) -> Option<Msg> {
+        let query = self.search_query.clone()?;
+        let shown = self.wall().archive();
+        let hits: Vec<(bool, usize, Option<usize>)> = self
+            .searched_notes()
+            .into_iter()
+            .flat_map(|(archive, note_index, note)| {
+                // The shown view sorts first
+                let other = archive != shown;
+                let title = note.title().unwrap_or_default();
+                let title_hit =
+                    substring_indices(&query, &title).map(|_| (other, note_index, None));
+                let todo_hits = filter::visible_todos(&note, TodoFilter::All)
+                    .into_iter()
+                    .filter(|(_, todo)| {
+                        substring_indices(&query, &todo.description().unwrap_or_default()).is_some()
+                    })
+                    .map(|(todo_index, _)| (other, note_index, Some(todo_index)))
+                    .collect::<Vec<_>>();
+                title_hit.into_iter().chain(todo_hits)
+            })
+            .collect();
+
+        let current = (
+            false,
+            self.selected_note_index,
+            match self.app.focus() {
+                Some(Id::TodoList) => self
+                    .selected_note()
+                    .and_then(|note| self.selected_todo(&note))
+                    .map(|(index, _)| index),
+                _ => None,
+            },
+        );
+        let found = match forward {
+            true => hits.iter().find(|hit| **hit > current).or(hits.first()),
+            false => hits
+                .iter()
+                .rev()
+                .find(|hit| **hit < current)
+                .or(hits.last()),
+        };
+        match found {
+            Some((other, note, todo)) => Some(Msg::JumpTo(shown != *other, *note, *todo)),
+            None => Some(Msg::Warning(format!("Pattern not found: {}", query))),
+        }
+    }
+
+    /// Cycle the status of the selected todo, the marked ones all take its new status
     fn switch_todo_status(&mut self) -> Option<Msg> {
-        let guard = self.notes_wall.write().unwrap();
-        if let Some(note) = guard.get_notes().get_mut(self.selected_note_index) {
-            if let Some(todo) = note.todos().get(self.selected_todo_index) {
-                let new_done = match todo.done().unwrap() {
-                    Some(true) => Some(false),
-                    Some(false) => None,
-                    None => Some(true),
-                };
-                assert!(todo.set_done(new_done).is_ok());
-                assert!(note.save().is_ok());
+        if let Some(note) = self.selected_note() {
+            let todos = self.acted_todos(&note);
+            let current = self
+                .selected_todo(&note)
+                .filter(|(_, todo)| todos.iter().any(|(_, other)| other.is(todo)))
+                .or(todos.first().cloned());
+            if let Some((_, todo)) = current {
+                let result = todo.done().and_then(|done| {
+                    let done = match done {
+                        Some(true) => Some(false),
+                        Some(false) => None,
+                        None => Some(true),
+                    };
+                    todos.iter().try_for_each(|(_, todo)| todo.set_done(done))
+                });
+                if self.report(result) {
+                    self.save_note(&note);
+                }
+            }
+        }
+        Some(Msg::ReloadTodoList)
+    }
+
+    fn move_note(&mut self, down: bool) -> Option<Msg> {
+        if self.sorted() {
+            return Some(Msg::Warning(
+                "Notes can only be moved in manual order, press s to change it".to_string(),
+            ));
+        }
+        let from = self.selected_note_index;
+        let len = self.wall().get_notes().len();
+        let to = match down {
+            true if from + 1 < len => from + 1,
+            false if from > 0 => from - 1,
+            _ => return None,
+        };
+        let result = self.wall_mut().move_note(from, to);
+        if self.report(result.context("Cannot save the order of the notes")) {
+            self.selected_note_index = to;
+        }
+        Some(Msg::ReloadNoteList)
+    }
+
+    /// Swap the selected todo with the previous or next one passing the filter
+    fn move_todo(&mut self, down: bool) -> Option<Msg> {
+        let mut note = self.selected_note()?;
+        let visible = self.visible_todos(&note);
+        let from = self.selected_todo_index;
+        let to = match down {
+            true if from + 1 < visible.len() => from + 1,
+            false if from > 0 => from - 1,
+            _ => return None,
+        };
+        if self.report(note.move_todo(visible[from].0, visible[to].0)) {
+            self.save_note(&note);
+            self.selected_todo_index = to;
+        }
+        Some(Msg::ReloadTodoList)
+    }
+
+    /// Pick one of the other notes, `target` builds the message sent with its index
+    fn open_note_picker(&mut self, title: &str, target: fn(usize) -> Msg) -> Option<Msg> {
+        if self.popup.is_some() || self.selected_note().is_none() {
+            return None;
+        }
+        let candidates: Vec<Candidate> = self
+            .wall()
+            .get_notes()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != self.selected_note_index)
+            .map(|(index, note)| Candidate::new(note.title().unwrap_or_default(), target(index)))
+            .collect();
+        if candidates.is_empty() {
+            return Some(Msg::Warning("There is no other note".to_string()));
+        }
+        self.open_popup(
+            Id::NotePicker,
+            Box::new(Finder::new(
+                title,
+                "",
+                candidates,
+                Matching::Fuzzy,
+                &self.theme,
+            )),
+        );
+        None
+    }
+
+    fn transfer_todo(&mut self, target: usize, copy: bool) -> Option<Msg> {
+        let source = self.selected_note()?;
+        let todos = self.acted_todos(&source);
+        let indices: Vec<usize> = todos.iter().map(|(index, _)| *index).collect();
+        let target = self.wall().get_notes().into_iter().nth(target)?;
+        let title = target.title().unwrap_or_default();
+        let result = self.wall().transfer_todos(&source, &indices, &target, copy);
+        let (verb, done) = match copy {
+            true => ("copy", "Copied"),
+            false => ("move", "Moved"),
+        };
+        let items = count_items(indices.len());
+        if self.report(
+            result.with_context(|| format!("Cannot {} the {} to \"{}\"", verb, items, title)),
+        ) {
+            self.notify(format!("{} {} to \"{}\"", done, items, title));
+            if !copy {
+                self.unselect(&todos);
+            }
+        }
+        self.refresh_note_table();
+        Some(Msg::ReloadTodoList)
+    }
+
+    fn merge_note(&mut self, source: usize) -> Option<Msg> {
+        let target = self.selected_note()?;
+        let source = self.wall().get_notes().into_iter().nth(source)?;
+        let title = source.title().unwrap_or_default();
+        let result = self.wall_mut().merge_notes(&target, &source);
+        if self.report(result.with_context(|| format!("Cannot merge \"{}\"", title))) {
+            self.notify(format!("Merged \"{}\"", title));
+        }
+        let notes = self.wall().get_notes();
+        self.selected_note_index = notes
+            .iter()
+            .position(|note| *note == target)
+            .unwrap_or_default();
+        Some(Msg::ReloadNoteList)
+    }
+
+    /// Visible rows from the range anchor to the selected todo, when the anchor is in this note
+    fn marked_range(&self) -> Option<RangeInclusive<usize>> {
+        let (note, anchor) = self.range_anchor.as_ref()?;
+        (Some(note) == self.selected_note().as_ref()).then(|| {
+            (*anchor).min(self.selected_todo_index)..=(*anchor).max(self.selected_todo_index)
+        })
+    }
+
+    fn refresh_marked_range(&mut self) -> Option<Msg> {
+        if self.range_anchor.is_some() {
+            self.refresh_todo_table();
+        }
+        None
+    }
+
+    fn toggle_range_anchor(&mut self) -> Option<Msg> {
+        self.range_anchor = match self.marked_range() {
+            Some(_) => None,
+            None => self
+                .selected_note()
+                .map(|note| (note, self.selected_todo_index)),
+        };
+        self.refresh_todo_table();
+        None
+    }
+
+    /// Marked todos of `note` with their index, in the note order
+    fn marked_todos(&self, note: &Note) -> Vec<(usize, Todo)> {
+        note.todos()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, todo)| self.selection.iter().any(|marked| marked.is(todo)))
+            .collect()
+    }
+
+    /// Todos a bulk action applies to: the marked ones of the note, else the selected one
+    fn acted_todos(&self, note: &Note) -> Vec<(usize, Todo)> {
+        let marked = self.marked_todos(note);
+        match marked.is_empty() {
+            true => self.selected_todo(note).into_iter().collect(),
+            false => marked,
+        }
+    }
+
+    fn unselect(&mut self, todos: &[(usize, Todo)]) {
+        self.selection
+            .retain(|marked| !todos.iter().any(|(_, todo)| todo.is(marked)));
+    }
+
+    /// Mark the range, or the selected todo, unmark them when they all are marked already
+    fn toggle_selection(&mut self) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let range = self
+            .marked_range()
+            .unwrap_or(self.selected_todo_index..=self.selected_todo_index);
+        let todos: Vec<(usize, Todo)> = self
+            .visible_todos(&note)
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| range.contains(position))
+            .map(|(_, todo)| todo)
+            .collect();
+        let marked = todos
+            .iter()
+            .all(|(_, todo)| self.selection.iter().any(|other| other.is(todo)));
+        self.unselect(&todos);
+        if !marked {
+            self.selection
+                .extend(todos.into_iter().map(|(_, todo)| todo));
+        }
+        self.range_anchor = None;
+        self.refresh_todo_table();
+        None
+    }
+
+    /// Move the marked range, or the selected todo, to a new note
+    fn split_note(&mut self) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let range = self
+            .marked_range()
+            .unwrap_or(self.selected_todo_index..=self.selected_todo_index);
+        let indices: Vec<usize> = self
+            .visible_todos(&note)
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| range.contains(position))
+            .map(|(_, (index, _))| index)
+            .collect();
+        if indices.is_empty() {
+            return None;
+        }
+        let result = self.wall_mut().split_note(&note, &indices);
+        match result.context("Cannot split the note") {
+            Ok(split) => self.notify(format!(
+                "Split {} items into \"{}\"",
+                indices.len(),
+                split.title().unwrap_or_default()
+            )),
+            Err(err) => {
+                self.report(Err(err));
             }
         }
+        self.range_anchor = None;
+        self.selected_todo_index = 0;
+        self.refresh_note_table();
+        Some(Msg::ReloadTodoList)
+    }
+
+    /// Prompt for one of `values`, the current one hinted. Picking one sends its message
+    fn open_value_picker(&mut self, title: &str, values: Vec<(String, Msg, bool)>) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        let candidates = values
+            .into_iter()
+            .map(|(label, target, current)| {
+                let candidate = Candidate::new(label, target);
+                match current {
+                    true => candidate.with_hint("current"),
+                    false => candidate,
+                }
+            })
+            .collect();
+        self.open_popup(
+            Id::NotePicker,
+            Box::new(Finder::new(
+                title,
+                "",
+                candidates,
+                Matching::Fuzzy,
+                &self.theme,
+            )),
+        );
+        None
+    }
+
+    fn pick_sort_mode(&mut self) -> Option<Msg> {
+        let current = self.wall().sort();
+        let values = SortMode::ALL
+            .into_iter()
+            .map(|sort| {
+                (
+                    sort.label().to_string(),
+                    Msg::SetSortMode(sort),
+                    sort == current,
+                )
+            })
+            .collect();
+        self.open_value_picker("Sort the notes by", values)
+    }
+
+    fn pick_todo_filter(&mut self) -> Option<Msg> {
+        let values = TodoFilter::ALL
+            .into_iter()
+            .map(|filter| {
+                (
+                    filter.label().to_string(),
+                    Msg::SetTodoFilter(filter),
+                    filter == self.todo_filter,
+                )
+            })
+            .collect();
+        self.open_value_picker("Show the items", values)
+    }
+
+    fn pick_theme(&mut self) -> Option<Msg> {
+        let values = self
+            .themes
+            .iter()
+            .map(|(name, theme)| {
+                (
+                    name.clone(),
+                    Msg::SetTheme(name.clone()),
+                    *theme == self.theme,
+                )
+            })
+            .collect();
+        self.open_value_picker("Theme", values)
+    }
+
+    /// The lists are mounted again with the theme, the other components get it when drawn or
+    /// opened
+    fn set_theme(&mut self, name: &str) -> Option<Msg> {
+        let theme = self
+            .themes
+            .iter()
+            .find(|(other, _)| other == name)?
+            .1
+            .clone();
+        self.theme = theme;
+        assert!(
+            self.app
+                .remount(
+                    Id::NoteList,
+                    Box::new(NoteList::new(self.key_mode, &self.theme)),
+                    vec![Self::mouse_subscription()]
+                )
+                .is_ok()
+        );
+        assert!(
+            self.app
+                .remount(
+                    Id::TodoList,
+                    Box::new(TodoList::new(self.key_mode, &self.theme)),
+                    vec![Self::mouse_subscription()]
+                )
+                .is_ok()
+        );
+        self.set_note_list_title();
+        self.refresh_note_table();
+        self.select_note_row();
+        self.notify(format!("Theme {}", name));
+        self.reload_todo_list()
+    }
+
+    fn set_sort_mode(&mut self, sort: SortMode) -> Option<Msg> {
+        let selected = self.selected_note();
+        self.wall_mut().set_sort(sort);
+        self.selected_note_index = selected
+            .and_then(|note| {
+                self.wall()
+                    .get_notes()
+                    .iter()
+                    .position(|other| *other == note)
+            })
+            .unwrap_or_default();
+        self.refresh_note_table();
+        self.select_note_row();
+        None
+    }
+
+    fn set_todo_filter(&mut self, filter: TodoFilter) -> Option<Msg> {
+        self.todo_filter = filter;
+        self.range_anchor = None;
+        self.selected_todo_index = 0;
+        Some(Msg::ReloadTodoList)
+    }
+
+    fn toggle_hide_completed(&mut self) -> Option<Msg> {
+        if let Some(note) = self.selected_note() {
+            if self.report(note.set_hide_completed(!note.hide_completed())) {
+                self.save_note(&note);
+            }
+            self.range_anchor = None;
+            self.selected_todo_index = 0;
+        }
         Some(Msg::ReloadTodoList)
     }
 
     fn remove_todo(&mut self) -> Option<Msg> {
-        let guard = self.notes_wall.write().unwrap();
-        if let Some(note) = guard.get_notes().get_mut(self.selected_note_index) {
-            if let Some(todo) = note.todos().get(self.selected_todo_index) {
-                assert!(note.remove_todo(todo).is_ok());
-                assert!(note.save().is_ok());
+        if let Some(mut note) = self.selected_note() {
+            let todos = self.acted_todos(&note);
+            if todos.is_empty() {
+                return Some(Msg::ReloadTodoList);
+            }
+            let result = todos
+                .iter()
+                .try_for_each(|(_, todo)| note.remove_todo(todo));
+            if self.report(result) {
+                if self.save_note(&note) {
+                    self.notify(format!("Deleted {}", count_items(todos.len())));
+                }
+                self.unselect(&todos);
                 self.selected_todo_index = 0;
             }
         }
         Some(Msg::ReloadTodoList)
     }
 
+    fn toggle_pinned(&mut self) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let title = note.title().unwrap_or_default();
+        let pinned = !note.pinned();
+        if self.report(note.set_pinned(pinned)) && self.save_note(&note) {
+            self.notify(match pinned {
+                true => format!("Pinned \"{}\"", title),
+                false => format!("Unpinned \"{}\"", title),
+            });
+        }
+        let notes = self.wall().get_notes();
+        self.selected_note_index = notes
+            .iter()
+            .position(|other| *other == note)
+            .unwrap_or_default();
+        Some(Msg::ReloadNoteList)
+    }
+
+    /// Archive the selected note, or restore it when the archive is shown
+    fn toggle_archived(&mut self) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let title = note.title().unwrap_or_default();
+        let archived = !note.archived();
+        if self.report(note.set_archived(archived)) && self.save_note(&note) {
+            self.notify(match archived {
+                true => format!("Archived \"{}\"", title),
+                false => format!("Restored \"{}\"", title),
+            });
+        }
+        let len = self.wall().get_notes().len();
+        self.selected_note_index = self.selected_note_index.min(len.saturating_sub(1));
+        Some(Msg::ReloadNoteList)
+    }
+
+    fn toggle_archive_view(&mut self) -> Option<Msg> {
+        let archive = !self.wall().archive();
+        self.wall_mut().set_archive(archive);
+        self.set_note_list_title();
+        self.selected_note_index = 0;
+        Some(Msg::ReloadNoteList)
+    }
+
+    fn set_note_list_title(&mut self) {
+        let title = match self.wall().archive() {
+            true => "Archive",
+            false => "Note List",
+        };
+        assert!(
+            self.app
+                .attr(
+                    &Id::NoteList,
+                    Attribute::Title,
+                    AttrValue::Title((title.to_string(), Alignment::Left))
+                )
+                .is_ok()
+        );
+    }
+
     fn remove_note(&mut self) -> Option<Msg> {
-        let mut guard = self.notes_wall.write().unwrap();
-        if let Some(note) = guard.get_notes().get(self.selected_note_index) {
-            assert!(guard.remove_note(note).is_ok());
+        if let Some(note) = self.selected_note() {
+            let title = note.title().unwrap_or_default();
+            let result = self.wall_mut().remove_note(&note);
+            if self.report(result.with_context(|| format!("Cannot delete \"{}\"", title))) {
+                self.notify(format!("Deleted note \"{}\"", title));
+            }
             self.selected_note_index = 0;
         }
         Some(Msg::ReloadNoteList)
     }
 
     fn add_note(&mut self) -> Option<Msg> {
-        self.selected_note_index = self.notes_wall.read().unwrap().get_notes().len();
-        self.notes_wall.write().unwrap().create_note();
+        self.selected_note_index = {
+            let mut wall = self.wall_mut();
+            let note = wall.create_note();
+            wall.get_notes()
+                .iter()
+                .position(|other| *other == note)
+                .unwrap_or_default()
+        };
         Some(Msg::EditNote)
     }
 
+    /// Offer the templates, if any, before adding a note
+    fn new_note(&mut self) -> Option<Msg> {
+        if self.popup.is_some() {
+            return None;
+        }
+        let templates = self.wall().templates();
+        let (templates, errors) = match templates {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.report(Err(err));
+                return self.add_note();
+            }
+        };
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors.iter().map(|err| format!("{:#}", err)).collect();
+            self.deferred
+                .push_back(Msg::Warning(format!("Skipped {}", errors.join(", "))));
+        }
+        if templates.is_empty() {
+            return self.add_note();
+        }
+        let candidates = std::iter::once(Candidate::new("Blank note", Msg::AddBlankNote))
+            .chain(templates.into_iter().map(|template| {
+                Candidate::new(
+                    &template.name,
+                    Msg::AddNoteFromTemplate(template.name.clone()),
+                )
+                .with_context("Template")
+            }))
+            .collect();
+        self.open_popup(
+            Id::NotePicker,
+            Box::new(Finder::new(
+                "New note",
+                "",
+                candidates,
+                Matching::Fuzzy,
+                &self.theme,
+            )),
+        );
+        None
+    }
+
+    fn add_note_from_template(&mut self, name: &str) -> Option<Msg> {
+        let templates = self.wall().templates();
+        let result = templates.and_then(|(templates, _)| {
+            let template = templates
+                .into_iter()
+                .find(|template| template.name == name)
+                .with_context(|| format!("The template {} doesn't exist anymore", name))?;
+            self.wall_mut().create_note_from_template(&template)
+        });
+        self.select_new_note(result.context("Cannot create the note"))
+    }
+
+    fn duplicate_note(&mut self) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let result = self.wall_mut().duplicate_note(&note);
+        self.select_new_note(result.context("Cannot duplicate the note"))
+    }
+
+    /// Save the note just created and select it
+    fn select_new_note(&mut self, note: Result<Note>) -> Option<Msg> {
+        let note = match note {
+            Ok(note) => note,
+            Err(err) => {
+                self.report(Err(err));
+                return None;
+            }
+        };
+        let notes = self.wall().get_notes();
+        self.selected_note_index = notes
+            .iter()
+            .position(|other| *other == note)
+            .unwrap_or_default();
+        if self.save_note(&note) {
+            self.notify(format!("Created \"{}\"", note.title().unwrap_or_default()));
+        }
+        Some(Msg::ReloadNoteList)
+    }
+
+    fn save_template(&mut self) -> Option<Msg> {
+        let note = self.selected_note()?;
+        let title = note.title().unwrap_or_default();
+        let result = self.wall().save_template(&note);
+        if self.report(result) {
+            self.notify(format!("Saved the template \"{}\"", title));
+        }
+        None
+    }
+
     fn add_todo(&mut self) -> Option<Msg> {
-        let guard = self.notes_wall.write().unwrap();
-        if let Some(note) = guard.get_notes().get_mut(self.selected_note_index) {
+        if let Some(mut note) = self.selected_note() {
             // note.create_todo().ok().map(|_| {
             //     self.selected_todo_index = note.todos().len() - 1;
             //     Some(Msg::EditTodo)
             // });
             if note.create_todo().is_ok() {
-                self.selected_todo_index = note.todos().len() - 1;
+                // New todos have an unknown status, make sure they are listed
+                if !self.todo_filter.matches(None) {
+                    self.todo_filter = TodoFilter::All;
+                }
+                self.selected_todo_index = self.visible_todos(&note).len() - 1;
                 return Some(Msg::EditTodo);
             }
         }
@@ -289,56 +1694,50 @@ impl Model {
     }
 
     fn update_note_todo(&mut self, description: Option<String>) -> Option<Msg> {
-        self.text_edit_popup_open = false;
-        assert!(self.app.umount(&Id::EditPopup).is_ok());
+        self.close_popup();
         if let Some(description) = description {
-            if let Some(note) = self
-                .notes_wall
-                .read()
-                .unwrap()
-                .get_notes()
-                .get(self.selected_note_index)
-            {
-                let _ = note.todos()[self.selected_todo_index].set_description(&description);
-                assert!(note.save().is_ok());
+            if let Some(note) = self.selected_note() {
+                if let Some((_, todo)) = self.selected_todo(&note) {
+                    let _ = todo.set_description(&description);
+                }
+                self.save_note(&note);
             }
         }
         Some(Msg::ReloadTodoList)
     }
 
     fn update_note_title(&mut self, title: Option<String>) -> Option<Msg> {
-        self.text_edit_popup_open = false;
-        assert!(self.app.umount(&Id::EditPopup).is_ok());
+        self.close_popup();
 
         if let Some(title) = title {
-            if let Some(note) = self
-                .notes_wall
-                .read()
-                .unwrap()
-                .get_notes()
-                .get(self.selected_note_index)
-            {
+            if let Some(note) = self.selected_note() {
                 let _ = note.set_title(&title);
-                assert!(note.save().is_ok());
+                self.save_note(&note);
             }
         }
 
         Some(Msg::ReloadNoteList)
     }
 
-    fn reload_note_list(&mut self) -> Option<Msg> {
+    /// Rebuild the rows keeping the selection, e.g. to update the unsaved markers
+    fn refresh_note_table(&mut self) {
+        let notes = self.wall().get_notes();
         assert!(
             self.app
                 .attr(
                     &Id::NoteList,
                     Attribute::Content,
                     AttrValue::Table(NoteList::build_table_note(
-                        self.notes_wall.read().unwrap().get_notes()
+                        notes,
+                        self.search_query.as_deref(),
+                        &self.theme
                     ))
                 )
                 .is_ok()
         );
+    }
 
+    fn select_note_row(&mut self) {
         assert!(
             self.app
                 .attr(
@@ -350,112 +1749,126 @@ impl Model {
                 )
                 .is_ok()
         );
+    }
 
+    fn reload_note_list(&mut self) -> Option<Msg> {
+        self.refresh_note_table();
+        self.select_note_row();
         self.selected_todo_index = 0;
         Some(Msg::ReloadTodoList)
     }
 
     fn prepare_note_edit_popup(&mut self) -> Option<Msg> {
-        if let Some(note) = self
-            .notes_wall
-            .read()
-            .unwrap()
-            .get_notes()
-            .get(self.selected_note_index)
-        {
-            self.text_edit_popup_open = true;
-            assert!(
-                self.app
-                    .remount(
-                        Id::EditPopup,
-                        Box::new(EditPopup::new(
-                            &note.title().unwrap(),
-                            "Title",
-                            EditPopupType::Note
-                        )),
-                        vec![]
-                    )
-                    .is_ok()
+        if let Some(note) = self.selected_note() {
+            self.open_popup(
+                Id::EditPopup,
+                Box::new(EditPopup::new(
+                    &note.title().unwrap(),
+                    "Title",
+                    EditPopupType::Note,
+                    &self.theme,
+                )),
             );
-            assert!(self.app.active(&Id::EditPopup).is_ok());
         }
         None
     }
 
     fn prepare_todo_edit_popup(&mut self) -> Option<Msg> {
-        if let Some(note) = self
-            .notes_wall
-            .read()
-            .unwrap()
-            .get_notes()
-            .get(self.selected_note_index)
-        {
-            if let Some(todo) = note.todos().get(self.selected_todo_index) {
-                self.text_edit_popup_open = true;
-                assert!(
-                    self.app
-                        .remount(
-                            Id::EditPopup,
-                            Box::new(EditPopup::new(
-                                &todo.description().unwrap(),
-                                "ToDo",
-                                EditPopupType::Todo
-                            )),
-                            vec![]
-                        )
-                        .is_ok()
+        if let Some(note) = self.selected_note() {
+            if let Some((_, todo)) = self.selected_todo(&note) {
+                self.open_popup(
+                    Id::EditPopup,
+                    Box::new(EditPopup::new(
+                        &todo.description().unwrap(),
+                        "ToDo",
+                        EditPopupType::Todo,
+                        &self.theme,
+                    )),
                 );
-                assert!(self.app.active(&Id::EditPopup).is_ok());
             }
         }
         None
     }
 
-    fn reload_todo_list(&mut self) -> Option<Msg> {
-        match self
-            .notes_wall
-            .read()
-            .unwrap()
-            .get_notes()
-            .get(self.selected_note_index)
-        {
-            Some(note) => {
-                assert!(
-                    self.app
-                        .attr(
-                            &Id::TodoList,
-                            Attribute::Content,
-                            AttrValue::Table(TodoList::build_table_todo(note.todos()))
-                        )
-                        .is_ok()
-                );
+    /// Rebuild the rows of the selected note keeping the selection
+    fn refresh_todo_table(&mut self) {
+        let todos = self
+            .selected_note()
+            .map(|note| {
+                self.visible_todos(&note)
+                    .into_iter()
+                    .map(|(_, todo)| todo)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let marked = self.marked_range();
+        let selected: Vec<usize> = self
+            .selected_note()
+            .map(|note| {
+                self.visible_todos(&note)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, todo))| self.selection.iter().any(|other| other.is(todo)))
+                    .map(|(position, _)| position)
+                    .collect()
+            })
+            .unwrap_or_default();
+        assert!(
+            self.app
+                .attr(
+                    &Id::TodoList,
+                    Attribute::Content,
+                    AttrValue::Table(TodoList::build_table_todo(
+                        todos,
+                        self.search_query.as_deref(),
+                        marked,
+                        &selected,
+                        &self.theme
+                    ))
+                )
+                .is_ok()
+        );
+    }
 
-                assert!(
-                    self.app
-                        .attr(
-                            &Id::TodoList,
-                            Attribute::Value,
-                            AttrValue::Payload(PropPayload::One(PropValue::Usize(
-                                self.selected_todo_index
-                            )))
-                        )
-                        .is_ok()
-                );
-            }
-            None => assert!(
+    fn reload_todo_list(&mut self) -> Option<Msg> {
+        self.refresh_todo_table();
+        if let Some(note) = self.selected_note() {
+            self.selected_todo_index = self
+                .selected_todo_index
+                .min(self.visible_todos(&note).len().saturating_sub(1));
+            assert!(
                 self.app
                     .attr(
                         &Id::TodoList,
-                        Attribute::Content,
-                        AttrValue::Table(TodoList::build_table_todo(vec![]))
+                        Attribute::Value,
+                        AttrValue::Payload(PropPayload::One(PropValue::Usize(
+                            self.selected_todo_index
+                        )))
                     )
                     .is_ok()
-            ),
+            );
         }
         None
     }
 }
 
+/// "item" or "N items"
+fn count_items(count: usize) -> String {
+    match count {
+        1 => "item".to_string(),
+        count => format!("{} items", count),
+    }
+}
+
+/// Path relative to the home directory when possible, as shells do
+fn display_path(path: &Path) -> String {
+    home_dir()
+        .and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf))
+        .map_or(path.display().to_string(), |relative| {
+            Path::new("~").join(relative).display().to_string()
+        })
+}
+
 struct NotesProvider {
     wall: SharedWall,
     init: Option<Result<()>>,
@@ -463,19 +1876,39 @@ struct NotesProvider {
 
 impl NotesProvider {
     fn new(wall: SharedWall) -> Self {
-        let init = Some(wall.write().unwrap().init());
+        let init = Some(wall.write().unwrap_or_else(PoisonError::into_inner).init());
 
         NotesProvider { wall, init }
     }
 }
 
+/// Sends the forwarded mouse events back as `AppEvent::Mouse`
+struct MouseReplay(MouseQueue);
+
+impl Poll<AppEvent> for MouseReplay {
+    fn poll(&mut self) -> ListenerResult<Option<Event<AppEvent>>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+            .map(|ev| Event::User(AppEvent::Mouse(ev))))
+    }
+}
+
 impl Poll<AppEvent> for NotesProvider {
     fn poll(&mut self) -> ListenerResult<Option<Event<AppEvent>>> {
         self.init.take().map_or(Ok(None), |result| match result {
             Ok(_) => Ok(Some(Event::User(AppEvent::NoteLoaded(
-                self.wall.read().unwrap().get_notes(),
+                self.wall
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get_notes(),
             )))),
-            Err(_) => Ok(Some(Event::User(AppEvent::ErrorInitialized))),
+            Err(err) => Ok(Some(Event::User(AppEvent::ErrorInitialized(format!(
+                "{:#}",
+                err
+            ))))),
         })
     }
 }
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Local, TimeDelta, Utc};
+
+use crate::{backend::Note, components::Progress};
+
+/// Days covered by the completions, today included
+pub static DAYS: usize = 30;
+static LONGEST_OPEN: usize = 5;
+
+/// Figures of the statistics view, over every note archived ones included
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Todos completed each day, the oldest first and today last
+    pub completions: Vec<u64>,
+    /// Title and progress of each note
+    pub notes: Vec<(String, Progress)>,
+    /// Mean time from creation to completion of the todos having both
+    pub average_completion: Option<TimeDelta>,
+    /// Open todos as note title, description and age, the oldest first
+    pub longest_open: Vec<(String, String, TimeDelta)>,
+}
+
+impl Stats {
+    pub fn compute(notes: &[Note], now: DateTime<Utc>) -> Self {
+        let today = now.with_timezone(&Local).date_naive();
+        let mut stats = Stats {
+            completions: vec![0; DAYS],
+            ..Default::default()
+        };
+        let mut durations = Vec::new();
+        for note in notes {
+            let title = note.title().unwrap_or_default();
+            stats.notes.push((title.clone(), Progress::of(note)));
+            for todo in note.todos() {
+                let created = todo.created().unwrap_or_default();
+                let completed = todo.completed().unwrap_or_default();
+                if let Some(completed) = completed {
+                    let days = (today - completed.with_timezone(&Local).date_naive()).num_days();
+                    if (0..DAYS as i64).contains(&days) {
+                        stats.completions[DAYS - 1 - days as usize] += 1;
+                    }
+                    if let Some(created) = created {
+                        durations.push(completed - created);
+                    }
+                }
+                if let (Some(created), Ok(Some(false))) = (created, todo.done()) {
+                    stats.longest_open.push((
+                        title.clone(),
+                        todo.description().unwrap_or_default(),
+                        now - created,
+                    ));
+                }
+            }
+        }
+        stats.average_completion = (!durations.is_empty())
+            .then(|| durations.iter().sum::<TimeDelta>() / durations.len() as i32);
+        stats.longest_open.sort_by(|a, b| b.2.cmp(&a.2));
+        stats.longest_open.truncate(LONGEST_OPEN);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+
+    use super::{DAYS, Stats};
+    use crate::backend::Note;
+
+    #[test]
+    fn stats_test() {
+        let mut note = Note::default();
+        assert!(note.set_title("note").is_ok());
+        for (description, done) in [
+            ("first", Some(true)),
+            ("second", Some(false)),
+            ("third", None),
+        ] {
+            let todo = note.create_todo().unwrap();
+            assert!(todo.set_description(description).is_ok());
+            assert!(todo.set_done(done).is_ok());
+        }
+
+        let now = Utc::now() + TimeDelta::hours(2);
+        let stats = Stats::compute(&[note], now);
+        assert_eq!(stats.completions.len(), DAYS);
+        assert_eq!(stats.completions.iter().sum::<u64>(), 1);
+        assert_eq!(stats.notes[0].1.done, 1);
+        assert!(stats.average_completion.unwrap() < TimeDelta::minutes(1));
+        assert_eq!(stats.longest_open.len(), 1);
+        assert_eq!(stats.longest_open[0].1, "second");
+        assert!(stats.longest_open[0].2 >= TimeDelta::hours(2));
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    backtrace::Backtrace,
+    fs::write,
+    ops::{Deref, DerefMut},
+    panic::{PanicHookInfo, set_hook, take_hook},
+    path::{Path, PathBuf},
+    thread,
+};
+
+use anyhow::Result;
+use chrono::Local;
+use tuirealm::terminal::{CrosstermTerminalAdapter, TerminalBridge};
+
+type Bridge = TerminalBridge<CrosstermTerminalAdapter>;
+
+/// Owns the terminal and gives it back to the shell when dropped, even while unwinding
+pub struct TerminalGuard {
+    bridge: Bridge,
+    restored: bool,
+}
+
+impl TerminalGuard {
+    pub fn init() -> Result<Self> {
+        let mut bridge = Bridge::new_crossterm()?;
+        bridge.enable_raw_mode()?;
+        bridge.enter_alternate_screen()?;
+        let _ = bridge.enable_mouse_capture();
+        Ok(Self {
+            bridge,
+            restored: false,
+        })
+    }
+
+    pub fn restore(&mut self) {
+        if !self.restored {
+            restore(&mut self.bridge);
+            self.restored = true;
+        }
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Bridge;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bridge
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.bridge
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+fn restore(bridge: &mut Bridge) {
+    let _ = bridge.disable_mouse_capture();
+    let _ = bridge.leave_alternate_screen();
+    let _ = bridge.disable_raw_mode();
+}
+
+/// Restore the terminal before the panic message is printed and save a crash report in `data_dir`
+pub fn install_panic_hook(data_dir: PathBuf) {
+    let hook = take_hook();
+    set_hook(Box::new(move |info| {
+        // The guard is out of reach here, a fresh bridge sends the same escape sequences
+        if let Ok(mut bridge) = Bridge::new_crossterm() {
+            restore(&mut bridge);
+        }
+        match write_crash_report(&data_dir, info) {
+            Ok(path) => eprintln!("Crash report saved in {}", path.display()),
+            Err(err) => eprintln!("Cannot save the crash report: {}", err),
+        }
+        hook(info);
+    }));
+}
+
+fn write_crash_report(data_dir: &Path, info: &PanicHookInfo) -> Result<PathBuf> {
+    let now = Local::now();
+    let path = data_dir.join(format!("crash-{}.log", now.format("%Y%m%d-%H%M%S")));
+    write(&path, crash_report(info, &now.to_rfc3339()))?;
+    Ok(path)
+}
+
+fn crash_report(info: &PanicHookInfo, time: &str) -> String {
+    let message = match info.payload().downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match info.payload().downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "Box<dyn Any>".to_string(),
+        },
+    };
+    let location = info
+        .location()
+        .map_or("unknown".to_string(), |location| location.to_string());
+    format!(
+        "todotui {} crashed at {}\nthread: {}\nlocation: {}\nmessage: {}\n\nbacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        time,
+        thread::current().name().unwrap_or("<unnamed>"),
+        location,
+        message,
+        Backtrace::force_capture()
+    )
+}